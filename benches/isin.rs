@@ -0,0 +1,16 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use securities_identifiery::isin::ISIN;
+
+fn parse_isin(c: &mut Criterion) {
+    c.bench_function("parse_isin", |b| {
+        b.iter(|| {
+            for isin in &["US0378331005", "US5949181045", "US38259P5089"] {
+                ISIN::parse_isin(black_box(isin)).unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, parse_isin);
+criterion_main!(benches);
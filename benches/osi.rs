@@ -0,0 +1,32 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use securities_identifiery::options::OptionData;
+
+const SYMBOLS: [&str; 3] = [
+    "AAPL  131101C00470000",
+    "SPX   141122P00019500",
+    "GOOGL2240119C01500000",
+];
+
+fn parse_osi(c: &mut Criterion) {
+    c.bench_function("parse_osi", |b| {
+        b.iter(|| {
+            for osi in &SYMBOLS {
+                OptionData::parse_osi(black_box(osi)).unwrap();
+            }
+        })
+    });
+}
+
+fn parse_osi_bytes(c: &mut Criterion) {
+    c.bench_function("parse_osi_bytes", |b| {
+        b.iter(|| {
+            for osi in &SYMBOLS {
+                OptionData::parse_osi_bytes(black_box(osi.as_bytes())).unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, parse_osi, parse_osi_bytes);
+criterion_main!(benches);
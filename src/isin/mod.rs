@@ -1,17 +1,83 @@
 use fancy_regex::Regex;
 
+use std::{cmp::Ordering, fmt, str::FromStr};
+
 use crate::options::Error;
 
 const ISIN_REGEX: &str =
     r"^(?P<country>[A-Z]{2})(?P<identifier>[A-Z0-9]{9})(?P<checksum>[0-9]{1})$";
 
+/// Reason an ISIN was rejected by [ISIN::diagnose]
 #[derive(Debug, PartialEq)]
+pub enum IsinError {
+    /// the ISIN is not 12 characters long
+    WrongLength { len: usize },
+    /// the first two characters are not uppercase letters
+    BadCountryCode,
+    /// the NSIN character at `position` of the ISIN (counted from 0) is not an uppercase letter or digit
+    BadBodyChar { position: usize },
+    /// the last character is not the check digit computed from the first 11
+    ChecksumMismatch { expected: u8, found: char },
+}
+
+impl ::std::error::Error for IsinError {}
+
+impl fmt::Display for IsinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IsinError::WrongLength { len } => {
+                write!(f, "ISIN has length {} and not 12", len)
+            }
+            IsinError::BadCountryCode => {
+                write!(f, "ISIN does not start with a two letter country code")
+            }
+            IsinError::BadBodyChar { position } => write!(
+                f,
+                "ISIN character at position {} is not an uppercase letter or digit",
+                position
+            ),
+            IsinError::ChecksumMismatch { expected, found } => write!(
+                f,
+                "ISIN check digit is {} but should be {}",
+                found, expected
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
 pub struct ISIN {
     isin: String,
 }
 
+/// ISINs are ordered by country code, then NSIN, then check digit
+impl Ord for ISIN {
+    fn cmp(&self, other: &ISIN) -> Ordering {
+        (self.country_code(), self.nsin(), self.check_digit()).cmp(&(
+            other.country_code(),
+            other.nsin(),
+            other.check_digit(),
+        ))
+    }
+}
+
+impl PartialOrd for ISIN {
+    fn partial_cmp(&self, other: &ISIN) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for ISIN {
+    type Err = Error;
+
+    fn from_str(isin: &str) -> Result<ISIN, Error> {
+        ISIN::parse_isin(isin)
+    }
+}
+
 impl ISIN {
     pub fn parse_isin(isin: &str) -> Result<ISIN, Error> {
+        let isin = isin.trim();
         let re = Regex::new(ISIN_REGEX);
         let re = match re {
             Ok(r) => r,
@@ -36,78 +102,141 @@ impl ISIN {
         }
     }
 
-    pub fn get_county_code(&self) -> &str {
+    /// two letter ISO 3166-1 country code, e.g. `US`
+    pub fn country_code(&self) -> &str {
         &self.isin[0..2]
     }
 
-    pub fn get_identifier(&self) -> &str {
+    /// nine character National Securities Identifying Number, e.g. the CUSIP for US securities
+    pub fn nsin(&self) -> &str {
         &self.isin[2..11]
     }
 
-    pub fn get_checksum(&self) -> &str {
+    /// trailing Luhn check digit
+    pub fn check_digit(&self) -> &str {
         &self.isin[11..]
     }
 
+    #[deprecated(note = "use `country_code` instead")]
+    pub fn get_county_code(&self) -> &str {
+        self.country_code()
+    }
+
+    #[deprecated(note = "use `nsin` instead")]
+    pub fn get_identifier(&self) -> &str {
+        self.nsin()
+    }
+
+    #[deprecated(note = "use `check_digit` instead")]
+    pub fn get_checksum(&self) -> &str {
+        self.check_digit()
+    }
+
     pub fn get_isin(&self) -> &str {
         &self.isin
     }
+
+    /// parses an ISIN like [ISIN::parse_isin] but tells which part of it is malformed
+    pub fn diagnose(isin: &str) -> Result<ISIN, IsinError> {
+        let isin = isin.trim();
+        let chars: Vec<char> = isin.chars().collect();
+        if chars.len() != 12 {
+            return Err(IsinError::WrongLength { len: chars.len() });
+        }
+        if !chars[..2].iter().all(|c| c.is_ascii_uppercase()) {
+            return Err(IsinError::BadCountryCode);
+        }
+        if let Some(position) = chars[2..11]
+            .iter()
+            .position(|c| !(c.is_ascii_uppercase() || c.is_ascii_digit()))
+        {
+            return Err(IsinError::BadBodyChar {
+                position: position + 2,
+            });
+        }
+
+        // all characters checked so far are ASCII, so the first 11 bytes are the payload
+        let expected = compute_checksum(&isin.as_bytes()[..11]);
+        let found = chars[11];
+        if found.to_digit(10) != Some(expected as u32) {
+            return Err(IsinError::ChecksumMismatch { expected, found });
+        }
+        Ok(ISIN {
+            isin: isin.to_string(),
+        })
+    }
+
+    /// checks format and check digit of an ISIN without keeping the parsed [ISIN]
+    pub fn is_valid(isin: &str) -> bool {
+        ISIN::parse_isin(isin).is_ok()
+    }
+
+    /// computes the check digit (0 to 9) for the first 11 characters of an ISIN, i.e. country code and NSIN
+    ///
+    /// Returns [Error::NoResult] if the input is not two uppercase letters followed by nine
+    /// uppercase letters or digits.
+    pub fn check_digit_for(country_and_nsin: &str) -> Result<u8, Error> {
+        let payload = country_and_nsin.as_bytes();
+        if payload.len() != 11
+            || !payload[..2].iter().all(|c| c.is_ascii_uppercase())
+            || !payload[2..]
+                .iter()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            return Err(Error::NoResult);
+        }
+        Ok(compute_checksum(payload))
+    }
 }
 
 fn verify_isin(isin: &str) -> bool {
-    let last_char = isin.as_bytes().last().copied().unwrap();
-    let checksum_char = compute_checksum(isin) + b'0';
+    let (payload, last_char) = isin.as_bytes().split_at(isin.len() - 1);
+    let checksum_char = compute_checksum(payload) + b'0';
 
-    last_char == checksum_char
+    last_char[0] == checksum_char
 }
 
 /// As described on:
 /// https://en.wikipedia.org/wiki/International_Securities_Identification_Number
 /// https://en.wikipedia.org/wiki/Luhn_algorithm
-fn compute_checksum(isin: &str) -> u8 {
-    let digits = replace_chars_to_numbers(isin);
-    let sum_odd: i32 = digits
-        .iter()
-        .rev()
-        .skip(1)
-        .step_by(2)
-        .map(|f| (*f as i32) * 2)
-        .flat_map(|f| if f > 9 { vec![f / 10, f % 10] } else { vec![f] })
-        .sum();
-    let sum_even: i32 = digits
-        .iter()
-        .rev()
-        .skip(2)
-        .step_by(2)
-        .map(|f| (*f as i32))
-        .sum();
-
-    let checksum = sum_even + sum_odd;
-
-    ((10 - (checksum % 10)) % 10) as u8
-}
-
-fn replace_chars_to_numbers(isin: &str) -> Vec<u8> {
-    isin.as_bytes()
-        .into_iter()
-        .flat_map(|f| convert_char_as_byte_to_numbers(f))
-        .collect::<Vec<u8>>()
-}
+///
+/// `payload` is the ISIN without its check digit. Letters are expanded to their two digits
+/// (A = 10 ... Z = 35) while iterating, so no intermediate buffer is allocated.
+fn compute_checksum(payload: &[u8]) -> u8 {
+    let mut sum: u32 = 0;
+    // the rightmost digit of the payload is doubled
+    let mut double = true;
+    let mut add_digit = |digit: u8| {
+        let value = if double {
+            digit as u32 * 2
+        } else {
+            digit as u32
+        };
+        sum += if value > 9 { value - 9 } else { value };
+        double = !double;
+    };
 
-fn convert_char_as_byte_to_numbers(c: &u8) -> Vec<u8> {
-    match c {
-        b'0'..=b'9' => vec![c - b'0'],
-        b'A'..=b'Z' => {
-            let index = c - b'A' + 10;
-            vec![index / 10, index % 10]
+    for c in payload.iter().rev() {
+        match c {
+            b'0'..=b'9' => add_digit(c - b'0'),
+            b'A'..=b'Z' => {
+                let index = c - b'A' + 10;
+                add_digit(index % 10);
+                add_digit(index / 10);
+            }
+            _ => {}
         }
-        _ => vec![],
     }
+
+    ((10 - (sum % 10)) % 10) as u8
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::collections::BTreeSet;
+
     #[test]
     fn validate_some_good_isins() {
         assert!(verify_isin("US0378331005")); // Apple
@@ -127,6 +256,14 @@ mod tests {
     }
 
     #[test]
+    fn compute_checksum_of_known_isins() {
+        assert_eq!(5, compute_checksum(b"US037833100")); // Apple
+        assert_eq!(5, compute_checksum(b"US594918104")); // Microsoft
+        assert_eq!(9, compute_checksum(b"US38259P508")); // Google
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn parse_isin() {
         let parsed = ISIN::parse_isin("US0378331005").unwrap();
         assert_eq!("US", parsed.get_county_code());
@@ -134,6 +271,126 @@ mod tests {
         assert_eq!("5", parsed.get_checksum());
     }
 
+    #[test]
+    fn isin_accessors() {
+        let parsed = ISIN::parse_isin("US38259P5089").unwrap();
+        assert_eq!("US", parsed.country_code());
+        assert_eq!("38259P508", parsed.nsin());
+        assert_eq!("9", parsed.check_digit());
+    }
+
+    #[test]
+    fn isin_ordering() {
+        let set: BTreeSet<ISIN> = [
+            "US5949181045", // Microsoft
+            "DE0007164600", // SAP
+            "US0378331005", // Apple
+            "GB0002634946", // BAE Systems
+            "DE0007100000", // Daimler
+        ]
+        .iter()
+        .map(|isin| ISIN::parse_isin(isin).unwrap())
+        .collect();
+
+        let ordered: Vec<&str> = set.iter().map(|isin| isin.get_isin()).collect();
+        assert_eq!(
+            vec![
+                "DE0007100000",
+                "DE0007164600",
+                "GB0002634946",
+                "US0378331005",
+                "US5949181045"
+            ],
+            ordered
+        );
+    }
+
+    #[test]
+    fn parse_isin_surrounding_whitespace() {
+        assert_eq!(
+            ISIN::parse_isin(" US0378331005\r\n").unwrap().get_isin(),
+            "US0378331005"
+        );
+    }
+
+    #[test]
+    fn is_valid() {
+        assert!(ISIN::is_valid("US0378331005")); // Apple
+        assert!(ISIN::is_valid("US5949181045")); // Microsoft
+        assert!(ISIN::is_valid("US38259P5089")); // Google
+
+        assert!(!ISIN::is_valid("US5949181040")); // Microsoft (checksum zeroed)
+        assert!(!ISIN::is_valid("US3825P95089")); // Google (two chars transposed)
+        assert!(!ISIN::is_valid("US037833100")); // Apple (no checksum)
+    }
+
+    #[test]
+    fn check_digit_for() {
+        assert_eq!(Ok(5), ISIN::check_digit_for("US037833100")); // Apple
+        assert_eq!(Ok(5), ISIN::check_digit_for("US594918104")); // Microsoft
+        assert_eq!(Ok(9), ISIN::check_digit_for("US38259P508")); // Google
+
+        assert_eq!(Err(Error::NoResult), ISIN::check_digit_for("US0378331005")); // too long
+        assert_eq!(Err(Error::NoResult), ISIN::check_digit_for("us037833100")); // lowercase
+    }
+
+    #[test]
+    fn isin_from_str() {
+        let parsed: ISIN = "US0378331005".parse().unwrap();
+        assert_eq!("US0378331005", parsed.get_isin());
+        assert_eq!(
+            Err(Error::ChecksumError),
+            "US0378331000".parse::<ISIN>() // Apple (checksum zeroed)
+        );
+    }
+
+    #[test]
+    fn diagnose() {
+        assert_eq!(
+            "US0378331005",
+            ISIN::diagnose("US0378331005").unwrap().get_isin()
+        );
+
+        assert_eq!(
+            Err(IsinError::WrongLength { len: 11 }),
+            ISIN::diagnose("US037833100") // Apple (no checksum)
+        );
+        assert_eq!(
+            Err(IsinError::WrongLength { len: 13 }),
+            ISIN::diagnose("US03783310055")
+        );
+        assert_eq!(
+            Err(IsinError::BadCountryCode),
+            ISIN::diagnose("U10378331005")
+        );
+        assert_eq!(
+            Err(IsinError::BadCountryCode),
+            ISIN::diagnose("us0378331005")
+        );
+        assert_eq!(
+            Err(IsinError::BadBodyChar { position: 5 }),
+            ISIN::diagnose("US037-331005")
+        );
+        assert_eq!(
+            Err(IsinError::BadBodyChar { position: 10 }),
+            ISIN::diagnose("US03783310b5")
+        );
+        assert_eq!(
+            Err(IsinError::ChecksumMismatch {
+                expected: 5,
+                found: '0'
+            }),
+            ISIN::diagnose("US0378331000") // Apple (checksum zeroed)
+        );
+        assert_eq!(
+            Err(IsinError::ChecksumMismatch {
+                expected: 5,
+                found: 'X'
+            }),
+            ISIN::diagnose("US037833100X")
+        );
+    }
+
     #[test]
     fn parse_isin_errors() {
         assert_eq!(ISIN::parse_isin("US0378331000"), Err(Error::ChecksumError)); // checksum wrong
@@ -1,3 +1,3 @@
 pub mod options;
 
-pub mod isin;
\ No newline at end of file
+pub mod isin;
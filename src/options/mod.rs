@@ -1,13 +1,31 @@
-use fancy_regex::Regex;
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use fancy_regex::{Captures, Regex};
 
-use strum_macros::{Display, EnumString};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString, IntoStaticStr};
 
-use std::{fmt, str::FromStr};
+use std::{collections::HashSet, convert::TryFrom, fmt, io::BufRead, ops::Deref, str::FromStr};
 
-const OCC_OSI_REGEX: &str = r"^(?=.{16,21}$)(?P<symbol>[\w]{1,6})\s{0,5}(?P<year>\d{2})(?P<month>0\d|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])(?P<contract>C|P|c|p)(?P<price>\d{8})$";
-const IB_ACTIVITY_STATEMENT_TRADES: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<day>0[1-9]|[12]\d|3[01])(?P<month>\w{3})(?P<year>\d{2})\s(?P<price>\d*[.]?\d+)\s(?P<contract>C|P|c|p)$"; //KO 28MAY21 32.01 C
+const OCC_OSI_REGEX: &str = r"^(?=.{16,21}$)(?P<symbol>[\w]{1,6})\s{0,5}(?P<year>\d{2})(?P<month>0[1-9]|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])(?P<contract>C|P|c|p)(?P<price>\d{8})$";
+const OCC_OSI_4DIGIT_YEAR_REGEX: &str = r"^(?=.{18,23}$)(?P<symbol>[\w]{1,6})\s{0,5}(?P<year>\d{4})(?P<month>0[1-9]|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])(?P<contract>C|P|c|p)(?P<price>\d{8})$"; //AAPL  20131101C00470000
+const IB_ACTIVITY_STATEMENT_TRADES: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<day>0[1-9]|[12]\d|3[01])(?P<month>\w{3})(?P<year>\d{2})\s(?P<price>\d{1,3}(?:,\d{3})+\.\d+|\d*[.]?\d+)\s(?P<contract>C|P|c|p)$"; //KO 28MAY21 32.01 C
+const SPACED_REGEX: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<year>\d{2})(?P<month>0[1-9]|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])\s(?P<contract>C|P|c|p)\s(?P<price>\d{1,3}(?:,\d{3})+\.\d+|\d*[.]?\d+)$"; //AAPL 131101 C 470.00
+const SCHWAB_REGEX: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<month>0[1-9]|1[0-2])/(?P<day>0[1-9]|[12]\d|3[01])/(?P<year>\d{4})\s(?P<price>\d{1,3}(?:,\d{3})+\.\d+|\d*[.]?\d+)\s(?P<contract>C|P|c|p)$"; //AAPL 11/01/2013 470.00 C
+const IB_TWS_REGEX: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<year>\d{4})(?P<month>0[1-9]|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])\s(?P<price>\d*[.]?\d+)\s(?P<contract>C|P|c|p)$"; //AAPL 20131101 470 C
+const RETAIL_REGEX: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<year>\d{4})-(?P<month>0[1-9]|1[0-2])-(?P<day>0[1-9]|[12]\d|3[01])\s(?P<contract>(?i:call|put))\s(?P<price>\d*[.]?\d+)$"; //AAPL 2013-11-01 call 470.00
+const TOS_REGEX: &str = r"^\.(?P<symbol>[\w]{1,6})(?P<year>\d{2})(?P<month>0[1-9]|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])(?P<contract>C|P|c|p)(?P<price>\d*[.]?\d+)$"; //.AAPL131101C470
+const OPRA_REGEX: &str = r"^(?P<symbol>[\w]{1,6})\s(?P<year>\d{2})(?P<month>0[1-9]|1[0-2])(?P<day>0[1-9]|[12]\d|3[01])\s(?P<contract>C|P|c|p)\s(?P<price>\d{8})$"; //AAPL 131101 C 00470000
 
-#[derive(Debug, Eq, PartialEq, EnumString, Display)]
+/// signature shared by the string parsers of [OptionData]
+type Parser = fn(&str) -> Result<OptionData, Error>;
+
+/// first expiration year which can be expressed by the two digit year of OSI
+pub const MIN_OSI_YEAR: i32 = 2000;
+/// last expiration year which can be expressed by the two digit year of OSI
+pub const MAX_OSI_YEAR: i32 = 2099;
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, EnumString, Display, EnumIter, IntoStaticStr)]
 enum Month3Letter {
     JAN = 1,
     FEB,
@@ -23,11 +41,23 @@ enum Month3Letter {
     DEC,
 }
 
+impl Month3Letter {
+    /// month for its number 1->12
+    fn from_number(month: i32) -> Option<Month3Letter> {
+        Month3Letter::iter().find(|m| *m as i32 == month)
+    }
+
+    /// uppercase three letter abbreviation as used in IB statements, e.g. `MAY`
+    fn to_str(self) -> &'static str {
+        self.into()
+    }
+}
+
 /// Struct representing a complete option contract
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct OptionData {
     /// ticker symbol
-    pub symbol: String,
+    pub symbol: Symbol,
     /// 4 digit year -> e.g. 2021
     expiration_year: i32,
     /// expiration month 1->12
@@ -36,15 +66,209 @@ pub struct OptionData {
     expiration_day: i32,
     pub strike_price: f64,
     pub contract_type: ContractType,
+    /// exchange or routing code a vendor appended to the symbol, e.g. `CBOE`
+    pub exchange: Option<String>,
+    /// whether the contract type letter was uppercase in the parsed input
+    contract_letter_uppercase: bool,
+    /// `$` or `.` in front of the symbol of an index option in the parsed input
+    index_prefix: Option<char>,
+    /// root including its weekly marker, set by [OptionData::strip_weekly_marker]
+    original_root: Option<Symbol>,
+}
+
+/// Option root of up to 6 uppercase letters or digits, e.g. `AAPL`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(String);
+
+impl Symbol {
+    /// uppercases `symbol` and checks that it has 1 to 6 letters or digits
+    pub fn new(symbol: &str) -> Result<Symbol, Error> {
+        let symbol = symbol.to_ascii_uppercase();
+        if symbol.is_empty()
+            || symbol.len() > 6
+            || !symbol
+                .bytes()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            return Err(Error::InvalidSymbol);
+        }
+        Ok(Symbol(symbol))
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // pad instead of write so the OSI serializers can align the symbol
+        f.pad(&self.0)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Symbol {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Strike in thousandths of a dollar, the resolution of the OSI price field
+///
+/// Formatting goes through integers, so strikes like `32.01` are written without floating point
+/// noise. [fmt::Display] writes as few decimals as needed, e.g. `470`, `32.01` or `0.5`.
+///
+/// A [Strike] always fits the 8 digit OSI price field, i.e. lies from 0 to 99999.999.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Strike(i64);
+
+/// largest strike in thousandths the 8 digit OSI price field can hold
+const MAX_STRIKE_THOUSANDTHS: i64 = 99_999_999;
+
+impl Strike {
+    /// `strike_price` in thousandths, fails with [Error::StrikeOutOfRange] if it does not fit the OSI price field
+    ///
+    /// Up to a millionth of a thousandth of floating point noise is rounded away, anything else
+    /// below a thousandth of a dollar is an error.
+    pub fn from_f64(strike_price: f64) -> Result<Strike, Error> {
+        let strike = Strike::nearest(strike_price);
+        let thousandths = strike_price * 1000_f64;
+        if thousandths.is_finite() && (thousandths - strike.0 as f64).abs() < 1e-6 {
+            Ok(strike)
+        } else {
+            Err(Error::StrikeOutOfRange)
+        }
+    }
+
+    /// `strike_price` rounded to the nearest thousandth and clamped to the OSI price field, `NaN` gives 0
+    fn nearest(strike_price: f64) -> Strike {
+        // `as` saturates and maps `NaN` to 0
+        let thousandths = (strike_price * 1000_f64).round() as i64;
+        Strike(thousandths.clamp(0, MAX_STRIKE_THOUSANDTHS))
+    }
+
+    /// strike in dollars
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64 / 1000_f64
+    }
+
+    /// 8 digit OSI price field, e.g. `00032010` for `32.01`
+    pub fn as_osi_field(&self) -> String {
+        format!("{:0>8}", self.0)
+    }
+
+    /// strike with exactly `decimals` decimals, e.g. `470.00` for 2, rounding half away from zero
+    pub fn as_decimal_string(&self, decimals: usize) -> String {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let places = decimals.min(3);
+        let divisor = 10_i64.pow(3 - places as u32);
+        let scaled = (self.0.abs() + divisor / 2) / divisor;
+        if places == 0 {
+            return format!("{}{}", sign, scaled);
+        }
+        let unit = 10_i64.pow(places as u32);
+        format!(
+            "{sign}{whole}.{fraction:0>places$}{zeros}",
+            sign = sign,
+            whole = scaled / unit,
+            fraction = scaled % unit,
+            places = places,
+            zeros = "0".repeat(decimals - places)
+        )
+    }
+}
+
+impl fmt::Display for Strike {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let decimal = self.as_decimal_string(3);
+        f.write_str(decimal.trim_end_matches('0').trim_end_matches('.'))
+    }
+}
+
+/// Two contracts are equal if they describe the same contract, regardless of how they were written
+/// or which exchange they were quoted on
+impl PartialEq for OptionData {
+    fn eq(&self, other: &OptionData) -> bool {
+        self.symbol == other.symbol
+            && self.expiration_year == other.expiration_year
+            && self.expiration_month == other.expiration_month
+            && self.expiration_day == other.expiration_day
+            && self.strike_price == other.strike_price
+            && self.contract_type == other.contract_type
+    }
+}
+
+/// Compares against the padded OSI symbol, see [OptionData::try_to_osi_string]
+///
+/// Contracts which have no OSI symbol are not equal to any string.
+impl PartialEq<str> for OptionData {
+    fn eq(&self, other: &str) -> bool {
+        self.try_to_osi_string().as_deref() == Ok(other)
+    }
+}
+
+impl<'a> PartialEq<&'a str> for OptionData {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+/// Builds a contract from `(symbol, expiration, strike, contract type)`, e.g. the columns of a CSV row
+///
+/// Runs the same checks as the parsers, see [OptionData::validate].
+impl<'a> TryFrom<(&'a str, NaiveDate, f64, ContractType)> for OptionData {
+    type Error = Error;
+
+    fn try_from(
+        (symbol, expiration, strike_price, contract_type): (&'a str, NaiveDate, f64, ContractType),
+    ) -> Result<OptionData, Error> {
+        OptionData::from_parts(
+            symbol,
+            expiration.year(),
+            expiration.month() as i32,
+            expiration.day() as i32,
+            strike_price,
+            contract_type,
+        )
+    }
 }
 
 /// Enum if it is a Call or a Put
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ContractType {
     Call,
     Put,
 }
 
+impl ContractType {
+    /// stable integer encoding for storage: `Call` = 0, `Put` = 1
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            ContractType::Call => 0,
+            ContractType::Put => 1,
+        }
+    }
+
+    /// inverse of [ContractType::as_u8], `None` for unknown values
+    pub fn from_u8(value: u8) -> Option<ContractType> {
+        match value {
+            0 => Some(ContractType::Call),
+            1 => Some(ContractType::Put),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for ContractType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -54,15 +278,87 @@ impl fmt::Display for ContractType {
     }
 }
 
+/// Enum classifying an expiration by its listing cycle
+#[derive(Debug, PartialEq)]
+pub enum ExpirationCycle {
+    Weekly,
+    Monthly,
+    Quarterly,
+    Leaps,
+}
+
+/// Date layout used by [OptionData::to_dated_string]
+#[derive(Debug, PartialEq)]
+pub enum DateFormat {
+    /// `MM/DD/YYYY`
+    UsSlash,
+    /// `DD/MM/YYYY`
+    EuSlash,
+    /// `YYYY-MM-DD`
+    Iso,
+}
+
+/// Symbologies supported by [reformat]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    /// OCC OSI, e.g. `AAPL  131101C00470000`
+    Osi,
+    /// IB activity statement, e.g. `AAPL 01NOV13 470 C`
+    Ib,
+    /// Schwab, e.g. `AAPL 11/01/2013 470.00 C`
+    Schwab,
+    /// Yahoo Finance, OSI without symbol padding, e.g. `AAPL131101C00470000`
+    Yahoo,
+    /// thinkorswim, e.g. `.AAPL131101C470`
+    Tos,
+}
+
+impl Format {
+    fn parse(self, s: &str) -> Result<OptionData, Error> {
+        match self {
+            Format::Osi | Format::Yahoo => OptionData::parse_osi(s),
+            Format::Ib => OptionData::parse_ib_activity_statement_trades_symbol(s),
+            Format::Schwab => OptionData::parse_schwab(s),
+            Format::Tos => OptionData::parse_tos(s),
+        }
+    }
+
+    fn format(self, option: &OptionData) -> Result<String, Error> {
+        match self {
+            Format::Schwab => {}
+            // the IB parser reads the two digit year relative to today, so the year has to come back
+            Format::Ib => {
+                let year = option.expiration_year;
+                if closest_year(year.rem_euclid(100), Local::now().year()) != year {
+                    return Err(Error::YearOutOfRange);
+                }
+            }
+            _ => option.check_two_digit_year()?,
+        }
+        Ok(match self {
+            Format::Osi => option.to_osi_string(),
+            Format::Ib => option.to_ib_activity_statement_string(),
+            Format::Schwab => option.to_schwab_string(),
+            Format::Yahoo => option.to_osi_string_no_symbol_padding(),
+            Format::Tos => option.to_tos_string(),
+        })
+    }
+}
+
 /// Error type which wraps [fancy_regex::Error]
 #[derive(Debug, PartialEq)]
 pub enum Error {
     NoResult,
+    LengthOutOfRange { len: usize },
     YearOutOfRange,
     MonthOutOfRange,
     DayOutOfRange,
     ChecksumError,
+    InvalidSymbol,
+    StrikeOutOfRange,
+    InvalidContractType,
     RegexError(String),
+    IoError(String),
 }
 
 impl ::std::error::Error for Error {}
@@ -73,8 +369,19 @@ impl fmt::Display for Error {
         // the error.
         match self {
             Error::NoResult => write!(f, "No Result for parsing String"),
+            Error::LengthOutOfRange { len } => {
+                write!(
+                    f,
+                    "Supplied String has length {} which the format does not allow",
+                    len
+                )
+            }
             Error::RegexError(e) => write!(f, "RegexError: {}", e),
-            Error::YearOutOfRange => write!(f, "Supplied year is out of range and not >2000"),
+            Error::IoError(e) => write!(f, "IoError: {}", e),
+            Error::YearOutOfRange => write!(
+                f,
+                "Supplied year does not fit the year field of the format or the supported dates"
+            ),
             Error::MonthOutOfRange => write!(
                 f,
                 "Supplied month is out of range and not between 1 and 12 "
@@ -85,118 +392,834 @@ impl fmt::Display for Error {
             Error::ChecksumError => {
                 write!(f, "Checksum could not be verified")
             }
+            Error::InvalidSymbol => {
+                write!(f, "Symbol is not made of 1 to 6 letters or digits")
+            }
+            Error::InvalidContractType => {
+                write!(f, "Contract type is not one of C or P")
+            }
+            Error::StrikeOutOfRange => write!(
+                f,
+                "Strike is not representable in the 8 digit OSI price field"
+            ),
+        }
+    }
+}
+
+/// Component of an OSI string, used by [OptionData::diagnose_osi]
+#[derive(Debug, PartialEq)]
+pub enum OsiField {
+    Length,
+    Symbol,
+    Year,
+    Month,
+    Day,
+    Contract,
+    Price,
+}
+
+/// A malformed component of an OSI string together with the offending substring
+#[derive(Debug, PartialEq)]
+pub struct FieldError {
+    pub field: OsiField,
+    pub value: String,
+}
+
+impl FieldError {
+    fn new(field: OsiField, value: &str) -> FieldError {
+        FieldError {
+            field,
+            value: value.to_string(),
         }
     }
 }
 
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Malformed {:?}: '{}'", self.field, self.value)
+    }
+}
+
 impl OptionData {
+    /// builds a contract from parsed parts, checking it with [OptionData::validate]
+    fn from_parts(
+        symbol: &str,
+        year: i32,
+        month: i32,
+        day: i32,
+        strike_price: f64,
+        contract_type: ContractType,
+    ) -> Result<OptionData, Error> {
+        let option = OptionData {
+            symbol: Symbol::new(symbol)?,
+            expiration_year: year,
+            expiration_month: month,
+            expiration_day: day,
+            strike_price,
+            contract_type,
+            contract_letter_uppercase: true,
+            index_prefix: None,
+            exchange: None,
+            original_root: None,
+        };
+        option.validate()?;
+        Ok(option)
+    }
+
     ///parse a string which is OSI compliant to [OptionData]
+    ///
+    /// A `$` or `.` in front of the symbol marks an index option, see [OptionData::is_index].
+    ///
+    /// Like all parsers of this module surrounding whitespace, including the `\r` of Windows
+    /// line endings, is ignored, and the result is checked with [OptionData::validate]: dates
+    /// which do not exist give [Error::MonthOutOfRange] or [Error::DayOutOfRange] and strikes
+    /// which do not fit the OSI price field, including decimal strikes with more than three
+    /// decimals like `32.0001`, give [Error::StrikeOutOfRange].
+    ///
+    /// Returns [Error::LengthOutOfRange] if the input is not 16 to 21 characters long and
+    /// [Error::NoResult] if it has the right length but does not match the OSI layout.
     pub fn parse_osi(osi: &str) -> Result<OptionData, Error> {
-        let re = Regex::new(OCC_OSI_REGEX);
-        let re = match re {
-            Ok(r) => r,
-            Err(e) => return Err(Error::RegexError(e.to_string())),
+        let (index_prefix, osi) = split_index_prefix(osi.trim());
+        let len = osi.chars().count();
+        if !(16..=21).contains(&len) {
+            return Err(Error::LengthOutOfRange { len });
+        }
+        let cap = captures(OCC_OSI_REGEX, osi)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            2000 + cap.name("year").unwrap().as_str().parse::<i32>().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            cap.name("price").unwrap().as_str().parse::<i32>().unwrap() as f64 / 1000_f64,
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        option.index_prefix = index_prefix;
+        Ok(option)
+    }
+
+    /// parse a padded 21 byte OSI field to [OptionData] without regex
+    ///
+    /// The fields are read from fixed positions: symbol `0..6` padded with trailing spaces,
+    /// date `6..12`, contract type `12` and strike `13..21`. Only the symbol is turned into a
+    /// string, after its bytes were checked to be ASCII letters and digits, and becomes a
+    /// [Symbol] like in the other parsers. Unlike [OptionData::parse_osi]
+    /// neither an unpadded symbol nor surrounding whitespace is accepted.
+    ///
+    /// Returns [Error::LengthOutOfRange] if `bytes` is not 21 bytes long and [Error::NoResult]
+    /// if a field holds unexpected characters.
+    pub fn parse_osi_bytes(bytes: &[u8]) -> Result<OptionData, Error> {
+        if bytes.len() != 21 {
+            return Err(Error::LengthOutOfRange { len: bytes.len() });
+        }
+        let symbol_len = bytes[..6]
+            .iter()
+            .rposition(|&b| b != b' ')
+            .map_or(0, |last| last + 1);
+        let symbol = &bytes[..symbol_len];
+        if !symbol.iter().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(Error::NoResult);
+        }
+        let contract_type = match bytes[12] {
+            b'C' | b'c' => ContractType::Call,
+            b'P' | b'p' => ContractType::Put,
+            _ => return Err(Error::NoResult),
         };
+        let field = |range: std::ops::Range<usize>| parse_ascii_digits(&bytes[range]);
+        let (year, month, day, price) =
+            match (field(6..8), field(8..10), field(10..12), field(13..21)) {
+                (Some(year), Some(month), Some(day), Some(price)) => (year, month, day, price),
+                _ => return Err(Error::NoResult),
+            };
+
+        let mut option = OptionData::from_parts(
+            // only ASCII letters and digits are left
+            std::str::from_utf8(symbol).map_err(|_| Error::NoResult)?,
+            2000 + year,
+            month,
+            day,
+            price as f64 / 1000_f64,
+            contract_type,
+        )?;
+        option.contract_letter_uppercase = bytes[12].is_ascii_uppercase();
+        Ok(option)
+    }
 
-        let result = re.captures(osi);
-        let result = match result {
-            Ok(r) => r,
-            Err(e) => return Err(Error::RegexError(e.to_string())),
+    /// parse an OSI string with a 4 digit year like `AAPL  20131101C00470000` to [OptionData]
+    ///
+    /// Some exports write the full year to avoid the century ambiguity of the two digit year
+    /// field, so the expiration is not restricted to [MIN_OSI_YEAR] to [MAX_OSI_YEAR].
+    pub fn parse_osi_4digit_year(osi: &str) -> Result<OptionData, Error> {
+        let osi = osi.trim();
+        let cap = captures(OCC_OSI_4DIGIT_YEAR_REGEX, osi)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            cap.name("year").unwrap().as_str().parse().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            cap.name("price").unwrap().as_str().parse::<i32>().unwrap() as f64 / 1000_f64,
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
+
+    /// parse a string which is OSI compliant to [OptionData] with an optional exchange suffix like `AAPL  131101C00470000:CBOE`
+    ///
+    /// The suffix is kept in [OptionData::exchange].
+    pub fn parse_osi_with_exchange(osi: &str) -> Result<OptionData, Error> {
+        let osi = osi.trim();
+        let (osi, exchange) = match osi.rfind(':') {
+            Some(i) => (&osi[..i], Some(&osi[i + 1..])),
+            None => (osi, None),
         };
-        if result.is_none() {
-            return Err(Error::NoResult);
+        if let Some(exchange) = exchange {
+            if exchange.is_empty() || !exchange.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(Error::NoResult);
+            }
+        }
+
+        let mut option = OptionData::parse_osi(osi)?;
+        option.exchange = exchange.map(|exchange| exchange.to_string());
+        Ok(option)
+    }
+
+    /// parse a string which is OSI compliant to [OptionData] and run a caller supplied consistency check on the result
+    ///
+    /// Returns [Error::ChecksumError] if `verifier` rejects the parsed contract.
+    pub fn parse_osi_with_verify<F>(osi: &str, verifier: F) -> Result<OptionData, Error>
+    where
+        F: Fn(&OptionData) -> bool,
+    {
+        let option = OptionData::parse_osi(osi)?;
+        if verifier(&option) {
+            Ok(option)
+        } else {
+            Err(Error::ChecksumError)
         }
-        let cap = result.unwrap();
+    }
 
-        Ok(OptionData {
-            expiration_year: 2000 + cap.name("year").unwrap().as_str().parse::<i32>().unwrap(),
-            expiration_month: cap.name("month").unwrap().as_str().parse().unwrap(),
-            expiration_day: cap.name("day").unwrap().as_str().parse().unwrap(),
+    /// parse a string which is OSI compliant to [OptionData], accepting only an uppercase `C` or `P`
+    ///
+    /// [OptionData::parse_osi] also accepts `c` and `p`, this variant returns [Error::NoResult]
+    /// for them.
+    pub fn parse_osi_strict_case(osi: &str) -> Result<OptionData, Error> {
+        let option = OptionData::parse_osi(osi)?;
+        if option.contract_letter_uppercase {
+            Ok(option)
+        } else {
+            Err(Error::NoResult)
+        }
+    }
 
-            symbol: cap.name("symbol").unwrap().as_str().parse().unwrap(),
-            contract_type: match cap.name("contract").unwrap().as_str() {
-                "P" | "p" => ContractType::Put,
-                "C" | "c" => ContractType::Call,
-                _ => panic!(),
-            },
-            strike_price: cap.name("price").unwrap().as_str().parse::<i32>().unwrap() as f64
-                / (1000 as f64),
-        })
+    /// parse a string which is OSI compliant to [OptionData], optionally moving legacy Saturday expirations to the Friday before
+    ///
+    /// Until 2015 monthly contracts were dated with the Saturday following the third Friday, while
+    /// trading ended on the Friday. The date alone does not tell which convention a symbol uses, so
+    /// with `shift_saturday_to_friday` every Saturday expiration is assumed to be a legacy one.
+    pub fn parse_osi_with_legacy_saturday(
+        osi: &str,
+        shift_saturday_to_friday: bool,
+    ) -> Result<OptionData, Error> {
+        let mut option = OptionData::parse_osi(osi)?;
+        let expiration = option.get_expiration_date();
+        if shift_saturday_to_friday && expiration.weekday() == Weekday::Sat {
+            option.set_expiration_date(expiration - Duration::days(1));
+        }
+        Ok(option)
     }
 
-    pub fn parse_ib_activity_statement_trades_symbol(osi: &str) -> Result<OptionData, Error> {
-        let re = Regex::new(IB_ACTIVITY_STATEMENT_TRADES);
-        let re = match re {
-            Ok(r) => r,
-            Err(e) => return Err(Error::RegexError(e.to_string())),
+    /// parse a string which is OSI compliant to [OptionData] and report every malformed field on failure
+    ///
+    /// The last 15 characters (date, contract type and price) have a fixed layout and are checked
+    /// position by position, everything in front of them is treated as the padded symbol.
+    pub fn diagnose_osi(osi: &str) -> Result<OptionData, Vec<FieldError>> {
+        let osi = osi.trim();
+        if let Ok(option) = OptionData::parse_osi(osi) {
+            return Ok(option);
+        }
+        let (_, osi) = split_index_prefix(osi);
+
+        let chars: Vec<char> = osi.chars().collect();
+        let mut errors = Vec::new();
+        if !(16..=21).contains(&chars.len()) {
+            errors.push(FieldError::new(OsiField::Length, osi));
+        }
+        if chars.len() < 15 {
+            return Err(errors);
+        }
+
+        let (symbol, tail) = chars.split_at(chars.len() - 15);
+        let symbol: String = symbol.iter().collect();
+        let field = |from: usize, to: usize| tail[from..to].iter().collect::<String>();
+        let (year, month, day) = (field(0, 2), field(2, 4), field(4, 6));
+        let (contract, price) = (field(6, 7), field(7, 15));
+
+        if Symbol::new(symbol.trim_end()).is_err() {
+            errors.push(FieldError::new(OsiField::Symbol, &symbol));
+        }
+
+        let year_number = parse_digits(&year);
+        if year_number.is_none() {
+            errors.push(FieldError::new(OsiField::Year, &year));
+        }
+        let month_number = parse_digits(&month).filter(|m| (1..=12).contains(m));
+        if month_number.is_none() {
+            errors.push(FieldError::new(OsiField::Month, &month));
+        }
+        let day_valid = match (year_number, month_number, parse_digits(&day)) {
+            (Some(y), Some(m), Some(d)) => is_day_in_month_and_year(2000 + y, m, d),
+            (_, _, Some(d)) => (1..=31).contains(&d),
+            (_, _, None) => false,
         };
+        if !day_valid {
+            errors.push(FieldError::new(OsiField::Day, &day));
+        }
+
+        if !matches!(contract.as_str(), "C" | "P" | "c" | "p") {
+            errors.push(FieldError::new(OsiField::Contract, &contract));
+        }
+        if parse_digits(&price).is_none() {
+            errors.push(FieldError::new(OsiField::Price, &price));
+        }
+
+        Err(errors)
+    }
 
-        let result = re.captures(osi);
-        let result = match result {
-            Ok(r) => r,
-            Err(e) => return Err(Error::RegexError(e.to_string())),
+    /// every problem [OptionData::diagnose_osi] finds in an OSI string as [Error], empty if it is valid
+    pub fn validate_osi_verbose(osi: &str) -> Vec<Error> {
+        let field_errors = match OptionData::diagnose_osi(osi) {
+            Ok(_) => return Vec::new(),
+            Err(field_errors) => field_errors,
         };
-        if result.is_none() {
-            return Err(Error::NoResult);
+        let mut errors: Vec<Error> = field_errors
+            .iter()
+            .map(|error| match error.field {
+                OsiField::Length => Error::LengthOutOfRange {
+                    len: error.value.chars().count(),
+                },
+                OsiField::Symbol => Error::InvalidSymbol,
+                OsiField::Year => Error::YearOutOfRange,
+                OsiField::Month => Error::MonthOutOfRange,
+                OsiField::Day => Error::DayOutOfRange,
+                OsiField::Contract => Error::InvalidContractType,
+                OsiField::Price => Error::StrikeOutOfRange,
+            })
+            .collect();
+        if errors.is_empty() {
+            errors.push(Error::NoResult);
         }
-        let cap = result.unwrap();
+        errors
+    }
+
+    /// parse an IB activity statement symbol like `KO 28MAY21 32.01 C` to [OptionData]
+    ///
+    /// The two digit year is resolved relative to today, see
+    /// [OptionData::parse_ib_activity_statement_trades_symbol_with_reference].
+    pub fn parse_ib_activity_statement_trades_symbol(osi: &str) -> Result<OptionData, Error> {
+        OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+            osi,
+            Local::now().date_naive(),
+        )
+    }
+
+    /// parse an IB activity statement symbol like `KO 28MAY21 32.01 C` to [OptionData], resolving the two digit year relative to `reference`
+    ///
+    /// The year is taken as the one closest to the year of `reference`, i.e. from 50 years before
+    /// up to 49 years after it. With a reference in 2021 `98` is read as 1998 and `70` as 2070,
+    /// while a statement from 1999 reads `98` as 1998 and `21` as 2021. Symbols more than 50 years
+    /// away from `reference` end up in the wrong century.
+    pub fn parse_ib_activity_statement_trades_symbol_with_reference(
+        osi: &str,
+        reference: NaiveDate,
+    ) -> Result<OptionData, Error> {
+        let re = Regex::new(IB_ACTIVITY_STATEMENT_TRADES)
+            .map_err(|e| Error::RegexError(e.to_string()))?;
+        OptionData::parse_ib_activity_statement_compiled(&re, osi, reference)
+    }
+
+    /// [OptionData::parse_ib_activity_statement_trades_symbol_with_reference] with the regex compiled by the caller
+    fn parse_ib_activity_statement_compiled(
+        re: &Regex,
+        osi: &str,
+        reference: NaiveDate,
+    ) -> Result<OptionData, Error> {
+        let osi = osi.trim();
+        let cap = captures_compiled(re, osi)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            closest_year(
+                cap.name("year").unwrap().as_str().parse().unwrap(),
+                reference.year(),
+            ),
+            Month3Letter::from_str(cap.name("month").unwrap().as_str())
+                .map_err(|_| Error::MonthOutOfRange)? as i32,
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            parse_decimal_strike(cap.name("price").unwrap().as_str()),
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
+
+    /// parse an IB activity statement symbol like [OptionData::parse_ib_activity_statement_trades_symbol] with `decimal_sep` as decimal separator of the strike, e.g. `KO 28MAY21 32,01 C`
+    ///
+    /// Commas as thousands separators, e.g. `1,250.00`, are only accepted with `.` as `decimal_sep`.
+    pub fn parse_ib_activity_statement_trades_symbol_locale(
+        osi: &str,
+        decimal_sep: char,
+    ) -> Result<OptionData, Error> {
+        // only the strike, the third field, may contain the separator
+        let normalized = osi
+            .trim()
+            .split(' ')
+            .enumerate()
+            .map(|(i, field)| {
+                if i == 2 {
+                    field.replace(decimal_sep, ".")
+                } else {
+                    field.to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
+        OptionData::parse_ib_activity_statement_trades_symbol(&normalized)
+    }
+
+    /// parse a thinkorswim symbol like `.AAPL131101C470` to [OptionData]
+    pub fn parse_tos(tos: &str) -> Result<OptionData, Error> {
+        let tos = tos.trim();
+        let cap = captures(TOS_REGEX, tos)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            2000 + cap.name("year").unwrap().as_str().parse::<i32>().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            cap.name("price").unwrap().as_str().parse::<f64>().unwrap(),
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
+
+    /// parse an OPRA style string with space separated OSI fields like `AAPL 131101 C 00470000` to [OptionData]
+    ///
+    /// The strike is given in thousandths of a dollar as in the OSI price field.
+    /// [OptionData::parse_spaced] would read the same string with a strike of 470000 dollars.
+    pub fn parse_opra(opra: &str) -> Result<OptionData, Error> {
+        let opra = opra.trim();
+        let cap = captures(OPRA_REGEX, opra)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            2000 + cap.name("year").unwrap().as_str().parse::<i32>().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            cap.name("price").unwrap().as_str().parse::<i32>().unwrap() as f64 / 1000_f64,
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
+
+    /// parse a space separated `SYMBOL YYMMDD C|P STRIKE` string like `AAPL 131101 C 470.00` to [OptionData]
+    ///
+    /// The strike may contain commas as thousands separators if it has a decimal point, e.g. `1,250.00`.
+    pub fn parse_spaced(spaced: &str) -> Result<OptionData, Error> {
+        let spaced = spaced.trim();
+        let cap = captures(SPACED_REGEX, spaced)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            2000 + cap.name("year").unwrap().as_str().parse::<i32>().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            parse_decimal_strike(cap.name("price").unwrap().as_str()),
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
+
+    /// parse an IB TWS contract description like `AAPL 20131101 470 C` to [OptionData]
+    pub fn parse_ib_tws(tws: &str) -> Result<OptionData, Error> {
+        let tws = tws.trim();
+        let cap = captures(IB_TWS_REGEX, tws)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            cap.name("year").unwrap().as_str().parse().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            cap.name("price").unwrap().as_str().parse::<f64>().unwrap(),
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
 
-        Ok(OptionData {
-            expiration_year: 2000 + cap.name("year").unwrap().as_str().parse::<i32>().unwrap(),
-            expiration_month: Month3Letter::from_str(cap.name("month").unwrap().as_str()).unwrap()
-                as i32,
-            expiration_day: cap.name("day").unwrap().as_str().parse().unwrap(),
+    /// parse a retail app row like `AAPL 2013-11-01 call 470.00` with an ISO date and a spelled out contract type to [OptionData]
+    pub fn parse_retail(retail: &str) -> Result<OptionData, Error> {
+        let retail = retail.trim();
+        let cap = captures(RETAIL_REGEX, retail)?;
 
-            symbol: cap.name("symbol").unwrap().as_str().parse().unwrap(),
-            contract_type: match cap.name("contract").unwrap().as_str() {
-                "P" | "p" => ContractType::Put,
-                "C" | "c" => ContractType::Call,
-                _ => panic!(),
+        OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            cap.name("year").unwrap().as_str().parse().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            cap.name("price").unwrap().as_str().parse::<f64>().unwrap(),
+            if cap
+                .name("contract")
+                .unwrap()
+                .as_str()
+                .eq_ignore_ascii_case("put")
+            {
+                ContractType::Put
+            } else {
+                ContractType::Call
             },
-            strike_price: cap.name("price").unwrap().as_str().parse::<f64>().unwrap(),
+        )
+    }
+
+    /// parse a string in any of the supported formats to [OptionData]
+    ///
+    /// Tries OSI, OSI with a 4 digit year, IB activity statement, Schwab, OPRA, space separated,
+    /// IB TWS, retail and thinkorswim layouts in that order. If a layout matches but the contract
+    /// is invalid, e.g. because of a non-existent date, that error is returned, otherwise
+    /// [Error::NoResult].
+    ///
+    /// OPRA is tried before the space separated layout, which would read the 8 digit OPRA price
+    /// field as a strike in dollars. An unpadded OSI symbol whose root ends in two digits, like
+    /// `AAPL20131101C00470000`, is read as OSI and not as OSI with a 4 digit year.
+    pub fn parse_any(s: &str) -> Result<OptionData, Error> {
+        let parsers: [Parser; 9] = [
+            OptionData::parse_osi,
+            OptionData::parse_osi_4digit_year,
+            OptionData::parse_ib_activity_statement_trades_symbol,
+            OptionData::parse_schwab,
+            OptionData::parse_opra,
+            OptionData::parse_spaced,
+            OptionData::parse_ib_tws,
+            OptionData::parse_retail,
+            OptionData::parse_tos,
+        ];
+        let mut error = Error::NoResult;
+        for parser in parsers.iter() {
+            match parser(s) {
+                Ok(option) => return Ok(option),
+                Err(Error::NoResult) | Err(Error::LengthOutOfRange { .. }) => {}
+                Err(e) => {
+                    if error == Error::NoResult {
+                        error = e;
+                    }
+                }
+            }
+        }
+        Err(error)
+    }
+
+    /// parse a string in any of the supported formats with [OptionData::parse_any] and serialize it to the padded 21 character OSI form
+    ///
+    /// Fails like [OptionData::try_to_osi_string], e.g. for an IB activity statement symbol read as 1998.
+    pub fn canonical_osi(s: &str) -> Result<String, Error> {
+        OptionData::parse_any(s)?.try_to_osi_string()
+    }
+
+    /// lazily parses every line of an IB activity statement with [OptionData::parse_ib_activity_statement_trades_symbol]
+    ///
+    /// Lines which do not look like a contract ([Error::NoResult]) are skipped, so headers and
+    /// other sections of the statement can be fed in unfiltered. Lines which look like a contract
+    /// but fail validation and read failures ([Error::IoError]) are yielded as errors.
+    pub fn parse_ib_reader<R: BufRead>(
+        reader: R,
+    ) -> impl Iterator<Item = Result<OptionData, Error>> {
+        // compiled once for the whole statement instead of once per line
+        let re = Regex::new(IB_ACTIVITY_STATEMENT_TRADES).map_err(|e| e.to_string());
+        let today = Local::now().date_naive();
+        reader.lines().filter_map(move |line| {
+            let result = match (line, &re) {
+                (Ok(line), Ok(re)) => {
+                    OptionData::parse_ib_activity_statement_compiled(re, &line, today)
+                }
+                (Ok(_), Err(e)) => Err(Error::RegexError(e.clone())),
+                (Err(e), _) => Err(Error::IoError(e.to_string())),
+            };
+            match result {
+                Err(Error::NoResult) => None,
+                result => Some(result),
+            }
         })
     }
 
+    /// parse a Schwab compliant string like `AAPL 11/01/2013 470.00 C` to [OptionData]
+    ///
+    /// The strike may contain commas as thousands separators if it has a decimal point, e.g. `1,250.00`.
+    pub fn parse_schwab(schwab: &str) -> Result<OptionData, Error> {
+        let schwab = schwab.trim();
+        let cap = captures(SCHWAB_REGEX, schwab)?;
+
+        let contract = cap.name("contract").unwrap().as_str();
+        let mut option = OptionData::from_parts(
+            cap.name("symbol").unwrap().as_str(),
+            cap.name("year").unwrap().as_str().parse().unwrap(),
+            cap.name("month").unwrap().as_str().parse().unwrap(),
+            cap.name("day").unwrap().as_str().parse().unwrap(),
+            parse_decimal_strike(cap.name("price").unwrap().as_str()),
+            contract_type_from_letter(contract),
+        )?;
+        option.contract_letter_uppercase = contract.chars().all(|c| c.is_ascii_uppercase());
+        Ok(option)
+    }
+
     /// serializes [OptionData] to a OSI compliant string like described here [https://ibkr.info/node/972]
+    ///
+    /// The two digit year field can only hold years from [MIN_OSI_YEAR] to [MAX_OSI_YEAR] and a
+    /// strike outside of [Strike] is clamped to the price field, use [OptionData::try_to_osi_string]
+    /// or [OptionData::try_serialize] if the contract may lie outside of those ranges.
     pub fn to_osi_string(&self) -> String {
         format!(
-            "{symbol:<6}{year:0>2}{month:0>2}{day:0>2}{contract}{price:0>8}",
+            "{symbol:<6}{year:0>2}{month:0>2}{day:0>2}{contract}{price}",
             symbol = self.symbol,
             day = self.expiration_day,
             month = self.expiration_month,
             year = self.expiration_year - 2000,
             contract = self.contract_type,
-            price = self.strike_price * 1000 as f64
+            price = self.nearest_strike().as_osi_field()
         )
         .to_string()
     }
 
+    /// serializes [OptionData] like [OptionData::to_osi_string] but restores the `$` or `.` prefix of a parsed index option in front of the symbol
+    pub fn to_index_osi_string(&self) -> String {
+        match self.index_prefix {
+            Some(prefix) => {
+                let osi = self.to_osi_string_no_symbol_padding();
+                let (symbol, date_contract_and_price) = osi.split_at(osi.len() - 15);
+                format!(
+                    "{root:<6}{rest}",
+                    root = format!("{}{}", prefix, symbol),
+                    rest = date_contract_and_price
+                )
+            }
+            None => self.to_osi_string(),
+        }
+    }
+
+    /// serializes [OptionData] like [OptionData::to_osi_string] followed by `:` and the exchange if there is one, the counterpart of [OptionData::parse_osi_with_exchange]
+    pub fn to_osi_string_with_exchange(&self) -> String {
+        match &self.exchange {
+            Some(exchange) => format!("{}:{}", self.to_osi_string(), exchange),
+            None => self.to_osi_string(),
+        }
+    }
+
+    /// serializes [OptionData] like [OptionData::to_osi_string] but fails with [Error::YearOutOfRange] if the expiration year does not fit the two digit year field
+    /// and with [Error::StrikeOutOfRange] if the strike does not fit the price field
+    pub fn try_to_osi_string(&self) -> Result<String, Error> {
+        self.try_serialize(OptionData::to_osi_string)
+    }
+
+    /// runs a serializer with a two digit year like [OptionData::to_tos_string], failing like [OptionData::try_to_osi_string] instead of writing a year or strike it cannot hold
+    ///
+    /// Covers all variants of [OptionData::to_osi_string] as well as the space separated, OPRA and
+    /// thinkorswim layouts, e.g. `option.try_serialize(OptionData::to_spaced_string)`.
+    pub fn try_serialize(&self, serializer: fn(&OptionData) -> String) -> Result<String, Error> {
+        self.check_two_digit_year()?;
+        self.strike()?;
+        Ok(serializer(self))
+    }
+
+    /// serializes [OptionData] like [OptionData::to_osi_string] but with a 4 digit year, e.g. `AAPL  20131101C00470000`
+    pub fn to_osi_string_4digit_year(&self) -> String {
+        format!(
+            "{symbol:<6}{year:0>4}{month:0>2}{day:0>2}{contract}{price}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = self.expiration_month,
+            year = self.expiration_year,
+            contract = self.contract_type,
+            price = self.nearest_strike().as_osi_field()
+        )
+    }
+
     /// serializes [OptionData] to a OSI compliant string like described here [https://ibkr.info/node/972] but without padding of the symbol to 6 chars
+    ///
+    /// Like [OptionData::to_osi_string] it needs a year from [MIN_OSI_YEAR] to [MAX_OSI_YEAR],
+    /// [OptionData::try_serialize] checks that.
     pub fn to_osi_string_no_symbol_padding(&self) -> String {
         format!(
-            "{symbol}{year:0>2}{month:0>2}{day:0>2}{contract}{price:0>8}",
+            "{symbol}{year:0>2}{month:0>2}{day:0>2}{contract}{price}",
             symbol = self.symbol,
             day = self.expiration_day,
             month = self.expiration_month,
             year = self.expiration_year - 2000,
             contract = self.contract_type,
-            price = self.strike_price * 1000 as f64
+            price = self.nearest_strike().as_osi_field()
         )
         .to_string()
     }
 
+    /// length of the string produced by [OptionData::to_osi_string], 21 for symbols of up to 6 characters
+    pub fn osi_padded_len(&self) -> usize {
+        self.symbol.len().max(6) + self.osi_unpadded_len() - self.symbol.len()
+    }
+
+    /// length of the string produced by [OptionData::to_osi_string_no_symbol_padding]
+    pub fn osi_unpadded_len(&self) -> usize {
+        // the year field only has two digits from MIN_OSI_YEAR to MAX_OSI_YEAR
+        let year_len = (self.expiration_year - 2000).to_string().len().max(2);
+        self.symbol.len() + year_len + 4 + 1 + 8
+    }
+
+    /// serializes [OptionData] like [OptionData::to_osi_string] but keeps the casing of the contract type letter from the parsed input
+    pub fn to_osi_string_preserving_case(&self) -> String {
+        let osi = self.to_osi_string();
+        if self.contract_letter_uppercase {
+            return osi;
+        }
+        let (symbol_and_date, contract_and_price) = osi.split_at(osi.len() - 9);
+        format!(
+            "{}{}",
+            symbol_and_date,
+            contract_and_price.to_ascii_lowercase()
+        )
+    }
+
+    /// serializes [OptionData] to a space separated string like `AAPL 131101 C 470.00`, the counterpart of [OptionData::parse_spaced]
+    ///
+    /// The two digit year only covers [MIN_OSI_YEAR] to [MAX_OSI_YEAR], check it with [OptionData::try_serialize].
+    pub fn to_spaced_string(&self) -> String {
+        format!(
+            "{symbol} {year:0>2}{month:0>2}{day:0>2} {contract} {price}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = self.expiration_month,
+            year = self.expiration_year - 2000,
+            contract = self.contract_type,
+            price = self.nearest_strike().as_decimal_string(2)
+        )
+    }
+
+    /// serializes [OptionData] to an IB TWS contract description like `AAPL 20131101 470 C`, the counterpart of [OptionData::parse_ib_tws]
+    pub fn to_ib_tws_string(&self) -> String {
+        format!(
+            "{symbol} {year:0>4}{month:0>2}{day:0>2} {price} {contract}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = self.expiration_month,
+            year = self.expiration_year,
+            contract = self.contract_type,
+            price = self.nearest_strike()
+        )
+    }
+
+    /// serializes [OptionData] to an IB activity statement symbol like `KO 28MAY21 32.01 C`, the counterpart of [OptionData::parse_ib_activity_statement_trades_symbol]
+    pub fn to_ib_activity_statement_string(&self) -> String {
+        format!(
+            "{symbol} {day:0>2}{month}{year:0>2} {price} {contract}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = Month3Letter::from_number(self.expiration_month)
+                .unwrap()
+                .to_str(),
+            year = self.expiration_year.rem_euclid(100),
+            contract = self.contract_type,
+            price = self.nearest_strike()
+        )
+    }
+
+    /// serializes [OptionData] to a thinkorswim symbol like `.AAPL131101C470`, the counterpart of [OptionData::parse_tos]
+    ///
+    /// Writes the year with two digits, use [OptionData::try_serialize] if the expiration may lie
+    /// outside of [MIN_OSI_YEAR] to [MAX_OSI_YEAR].
+    pub fn to_tos_string(&self) -> String {
+        format!(
+            ".{symbol}{year:0>2}{month:0>2}{day:0>2}{contract}{price}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = self.expiration_month,
+            year = self.expiration_year - 2000,
+            contract = self.contract_type,
+            price = self.nearest_strike()
+        )
+    }
+
+    /// serializes [OptionData] to an OPRA style string like `AAPL 131101 C 00470000`, the counterpart of [OptionData::parse_opra]
+    ///
+    /// Year and price fields are the ones of [OptionData::to_osi_string], [OptionData::try_serialize] fails for contracts they cannot hold.
+    pub fn to_opra_string(&self) -> String {
+        format!(
+            "{symbol} {year:0>2}{month:0>2}{day:0>2} {contract} {price}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = self.expiration_month,
+            year = self.expiration_year - 2000,
+            contract = self.contract_type,
+            price = self.nearest_strike().as_osi_field()
+        )
+    }
+
     /// serializes [OptionData] to a Schwab compliant string like described here [http://www.schwabcontent.com/symbology/int_eng/key_details.html]
     pub fn to_schwab_string(&self) -> String {
+        self.to_dated_string(DateFormat::UsSlash)
+    }
+
+    /// serializes [OptionData] in the Schwab layout with the date written in the given [DateFormat]
+    pub fn to_dated_string(&self, date_format: DateFormat) -> String {
+        let date = match date_format {
+            DateFormat::UsSlash => format!(
+                "{month:0>2}/{day:0>2}/{year:0>4}",
+                day = self.expiration_day,
+                month = self.expiration_month,
+                year = self.expiration_year
+            ),
+            DateFormat::EuSlash => format!(
+                "{day:0>2}/{month:0>2}/{year:0>4}",
+                day = self.expiration_day,
+                month = self.expiration_month,
+                year = self.expiration_year
+            ),
+            DateFormat::Iso => format!(
+                "{year:0>4}-{month:0>2}-{day:0>2}",
+                day = self.expiration_day,
+                month = self.expiration_month,
+                year = self.expiration_year
+            ),
+        };
         format!(
-            "{symbol} {month:0>2}/{day:0>2}/{year:0>4} {price:.2} {contract}",
+            "{symbol} {date} {price} {contract}",
             symbol = self.symbol,
+            date = date,
+            contract = self.contract_type,
+            price = self.nearest_strike().as_decimal_string(2)
+        )
+    }
+
+    /// serializes [OptionData] to a single line of JSON like `{"symbol":"AAPL","expiration":"2013-11-01","strike":470.0,"type":"C"}`
+    ///
+    /// Written by hand so logging does not need a JSON library.
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"symbol":"{symbol}","expiration":"{year:0>4}-{month:0>2}-{day:0>2}","strike":{price:?},"type":"{contract}"}}"#,
+            symbol = escape_json(&self.symbol),
             day = self.expiration_day,
             month = self.expiration_month,
             year = self.expiration_year,
             contract = self.contract_type,
-            price = self.strike_price as f64
+            price = self.nearest_strike().as_f64()
         )
-        .to_string()
     }
 
     pub fn get_expiration_year(&self) -> i32 {
@@ -211,31 +1234,515 @@ impl OptionData {
         self.expiration_day
     }
 
+    /// expiration as a calendar date
+    pub fn get_expiration_date(&self) -> NaiveDate {
+        // the parsers only hand out dates that passed `check_ymd`
+        NaiveDate::from_ymd_opt(
+            self.expiration_year,
+            self.expiration_month as u32,
+            self.expiration_day as u32,
+        )
+        .expect("expiration fields always form a valid date")
+    }
+
+    /// expiration as a timestamp at the caller supplied settlement time, e.g. 16:00 for PM-settled contracts
+    ///
+    /// AM-settled index options (like standard monthly SPX) settle on the opening prices, so pass
+    /// the market open for those.
+    pub fn expiration_datetime(&self, tz_close: NaiveTime) -> NaiveDateTime {
+        self.get_expiration_date().and_time(tz_close)
+    }
+
+    /// classifies the expiration into an [ExpirationCycle] as seen from `today`
+    ///
+    /// The classification is a heuristic based on the date alone:
+    /// * more than 365 days after `today` -> [ExpirationCycle::Leaps]
+    /// * third Friday of March, June, September or December -> [ExpirationCycle::Quarterly]
+    /// * third Friday of any other month -> [ExpirationCycle::Monthly]
+    /// * everything else -> [ExpirationCycle::Weekly]
+    ///
+    /// Expirations moved to the Thursday because of an exchange holiday are reported as weekly.
+    pub fn expiration_cycle(&self, today: NaiveDate) -> ExpirationCycle {
+        let expiration = self.get_expiration_date();
+        if expiration.signed_duration_since(today) > Duration::days(365) {
+            return ExpirationCycle::Leaps;
+        }
+        if !is_third_friday(expiration) {
+            return ExpirationCycle::Weekly;
+        }
+        match self.expiration_month {
+            3 | 6 | 9 | 12 => ExpirationCycle::Quarterly,
+            _ => ExpirationCycle::Monthly,
+        }
+    }
+
+    /// re-checks the invariants the parsers guarantee, returning the first violation
+    ///
+    /// Checks that the expiration is a valid date and that the strike fits the OSI price field,
+    /// the symbol is always valid as [Symbol] checks it on construction.
+    ///
+    /// Every parser runs these checks on its result, see [OptionData::parse_osi].
+    pub fn validate(&self) -> Result<(), Error> {
+        check_ymd(
+            self.expiration_year,
+            self.expiration_month,
+            self.expiration_day,
+        )?;
+        Strike::from_f64(self.strike_price)?;
+        Ok(())
+    }
+
+    /// the Saturday after a Friday expiration, the date OCC used for monthly contracts before 2015
+    ///
+    /// Expirations on other weekdays are returned unchanged.
+    pub fn occ_settlement_saturday(&self) -> NaiveDate {
+        let expiration = self.get_expiration_date();
+        if expiration.weekday() == Weekday::Fri {
+            expiration + Duration::days(1)
+        } else {
+            expiration
+        }
+    }
+
     pub fn set_ymd(&self, year: i32, month: i32, day: i32) -> Result<(), Error> {
-        if !year > 2000 {
+        if year < 2000 {
             return Err(Error::YearOutOfRange);
         }
-        if !(month >= 1 && month <= 12) {
-            return Err(Error::MonthOutOfRange);
+        check_ymd(year, month, day)
+    }
+
+    /// the same contract expiring on the given date
+    ///
+    /// Fails with [Error::MonthOutOfRange] or [Error::DayOutOfRange] if the date does not exist
+    /// and with [Error::YearOutOfRange] if a [NaiveDate] cannot hold it. Whether the year fits a
+    /// two digit year field is left to the serializers, see [OptionData::try_to_osi_string].
+    pub fn roll_to(&self, year: i32, month: i32, day: i32) -> Result<OptionData, Error> {
+        check_ymd(year, month, day)?;
+        let expiration =
+            NaiveDate::from_ymd_opt(year, month as u32, day as u32).ok_or(Error::YearOutOfRange)?;
+        let mut rolled = self.clone();
+        rolled.set_expiration_date(expiration);
+        Ok(rolled)
+    }
+
+    /// the same contract expiring the given number of calendar days later, or earlier if negative
+    pub fn roll_days(&self, days: i64) -> Result<OptionData, Error> {
+        let expiration = Duration::try_days(days)
+            .and_then(|offset| self.get_expiration_date().checked_add_signed(offset))
+            .ok_or(Error::YearOutOfRange)?;
+        self.roll_to(
+            expiration.year(),
+            expiration.month() as i32,
+            expiration.day() as i32,
+        )
+    }
+
+    /// whether the symbol was marked as an index with a `$` or `.` prefix when parsed
+    ///
+    /// OSI itself cannot express the prefix, so it is only kept by
+    /// [OptionData::to_index_osi_string].
+    pub fn is_index(&self) -> bool {
+        self.index_prefix.is_some()
+    }
+
+    /// the option root as it appears in the OSI symbol, e.g. `AAPL1` for an adjusted contract
+    pub fn option_root(&self) -> &str {
+        &self.symbol
+    }
+
+    /// removes a trailing `1` to `5` week number from the root of a Friday expiration
+    ///
+    /// Some vendors mark weeklies by appending the week of the month to the root (`AAPL2`),
+    /// while OSI uses the plain root with the actual expiration date. The root before stripping
+    /// is kept in [OptionData::original_root]. Roots of other expirations, a root consisting of
+    /// the digit only and already stripped contracts are left unchanged.
+    pub fn strip_weekly_marker(&mut self) {
+        if self.original_root.is_some() || self.get_expiration_date().weekday() != Weekday::Fri {
+            return;
         }
-        if !is_day_in_month_and_year(year, month, day) {
-            return Err(Error::DayOutOfRange);
+        let mut chars = self.symbol.chars();
+        match chars.next_back() {
+            Some('1'..='5') if !chars.as_str().is_empty() => {
+                let stripped = Symbol(chars.as_str().to_string());
+                self.original_root = Some(std::mem::replace(&mut self.symbol, stripped));
+            }
+            _ => {}
+        }
+    }
+
+    /// root including its weekly marker if [OptionData::strip_weekly_marker] removed one
+    pub fn original_root(&self) -> Option<&str> {
+        self.original_root.as_deref()
+    }
+
+    /// ticker of the underlying with a trailing adjustment digit of the root removed
+    ///
+    /// This is a heuristic: OCC marks adjusted contracts (e.g. after splits or special dividends)
+    /// by appending a single digit to the root, so `AAPL1` is reported as `AAPL`. Tickers which
+    /// genuinely end in a digit are shortened as well.
+    pub fn underlying_symbol(&self) -> String {
+        let mut chars = self.symbol.chars();
+        match chars.next_back() {
+            Some(c) if c.is_ascii_digit() && !chars.as_str().is_empty() => {
+                chars.as_str().to_string()
+            }
+            _ => self.symbol.to_string(),
+        }
+    }
+
+    /// whether the root carries the trailing `7` of a mini option, e.g. `AAPL7`
+    ///
+    /// Mini options deliver 10 instead of 100 shares. OCC marked them with a `7` after the root
+    /// while they were listed from 2013 on; this is a heuristic since the convention was
+    /// transitional and adjusted contracts may end in a `7` as well.
+    pub fn is_mini(&self) -> bool {
+        self.symbol.len() > 1 && self.symbol.ends_with('7')
+    }
+
+    /// value of exercising the contract at the given underlying price, never negative
+    pub fn intrinsic_value(&self, underlying: f64) -> f64 {
+        match self.contract_type {
+            ContractType::Call => (underlying - self.strike_price).max(0.0),
+            ContractType::Put => (self.strike_price - underlying).max(0.0),
+        }
+    }
+
+    /// time value contained in the option price, clamped at zero for quotes below intrinsic value
+    pub fn extrinsic_value(&self, option_price: f64, underlying: f64) -> f64 {
+        (option_price - self.intrinsic_value(underlying)).max(0.0)
+    }
+
+    /// underlying price at expiration at which a bought contract neither gains nor loses
+    pub fn break_even(&self, premium_paid: f64) -> f64 {
+        match self.contract_type {
+            ContractType::Call => self.strike_price + premium_paid,
+            ContractType::Put => self.strike_price - premium_paid,
+        }
+    }
+
+    /// compares two contracts ignoring floating point noise in the strike
+    ///
+    /// Symbol casing and padding need no special treatment as [Symbol] is normalized on
+    /// construction.
+    pub fn semantically_eq(&self, other: &OptionData) -> bool {
+        self.symbol == other.symbol
+            && self.expiration_year == other.expiration_year
+            && self.expiration_month == other.expiration_month
+            && self.expiration_day == other.expiration_day
+            && self.nearest_strike() == other.nearest_strike()
+            && self.contract_type == other.contract_type
+    }
+
+    /// the same contract with the given [ContractType]
+    pub fn with_contract_type(mut self, contract_type: ContractType) -> OptionData {
+        self.contract_type = contract_type;
+        self
+    }
+
+    /// the call and the put of the same symbol, expiration and strike, in that order
+    pub fn straddle_pair(&self) -> (OptionData, OptionData) {
+        (
+            self.clone().with_contract_type(ContractType::Call),
+            self.clone().with_contract_type(ContractType::Put),
+        )
+    }
+
+    /// copies of `base` for every strike from `low` to `high`, both inclusive, spaced by `increment`
+    ///
+    /// Returns [Error::StrikeOutOfRange] if `increment` is not positive or any of the three
+    /// prices cannot be written to the OSI price field. `low` above `high` gives an empty ladder.
+    pub fn strike_ladder(
+        base: &OptionData,
+        low: f64,
+        high: f64,
+        increment: f64,
+    ) -> Result<Vec<OptionData>, Error> {
+        let low = Strike::from_f64(low)?;
+        let high = Strike::from_f64(high)?;
+        let step = Strike::from_f64(increment)?;
+        if step.0 == 0 {
+            return Err(Error::StrikeOutOfRange);
+        }
+
+        Ok((low.0..=high.0)
+            .step_by(step.0 as usize)
+            .map(|thousandths| {
+                let mut option = base.clone();
+                option.strike_price = Strike(thousandths).as_f64();
+                option
+            })
+            .collect())
+    }
+
+    /// [OptionData::strike_price] as [Strike], fails with [Error::StrikeOutOfRange] like [Strike::from_f64]
+    pub fn strike(&self) -> Result<Strike, Error> {
+        Strike::from_f64(self.strike_price)
+    }
+
+    /// strike rounded and clamped to the OSI price field, used by the infallible serializers
+    fn nearest_strike(&self) -> Strike {
+        Strike::nearest(self.strike_price)
+    }
+
+    /// stable key identifying the contract independent of the format it was parsed from, e.g. `AAPL|2013-11-01|C|470000`
+    ///
+    /// The strike is given in thousandths of a dollar. The key is meant for caches and
+    /// idempotency checks and is not a broker format.
+    pub fn cache_key(&self) -> String {
+        format!(
+            "{symbol}|{year:0>4}-{month:0>2}-{day:0>2}|{contract}|{price}",
+            symbol = self.symbol,
+            day = self.expiration_day,
+            month = self.expiration_month,
+            year = self.expiration_year,
+            contract = self.contract_type,
+            price = self.nearest_strike().0
+        )
+    }
+
+    fn set_expiration_date(&mut self, date: NaiveDate) {
+        self.expiration_year = date.year();
+        self.expiration_month = date.month() as i32;
+        self.expiration_day = date.day() as i32;
+    }
+
+    /// fails with [Error::YearOutOfRange] if the expiration year does not fit a two digit year field
+    fn check_two_digit_year(&self) -> Result<(), Error> {
+        if !(MIN_OSI_YEAR..=MAX_OSI_YEAR).contains(&self.expiration_year) {
+            return Err(Error::YearOutOfRange);
         }
         Ok(())
     }
+
+    /// expiration as a sortable `(year, month, day)` tuple
+    fn expiration_key(&self) -> (i32, i32, i32) {
+        (
+            self.expiration_year,
+            self.expiration_month,
+            self.expiration_day,
+        )
+    }
+}
+
+/// converts a contract from one [Format] to another
+pub fn reformat(input: &str, from: Format, to: Format) -> Result<String, Error> {
+    let option = from.parse(input)?;
+    to.format(&option)
+}
+
+/// contract with the nearest expiration, the first one on ties, `None` for an empty slice
+pub fn soonest_expiration(options: &[OptionData]) -> Option<&OptionData> {
+    options.iter().min_by_key(|option| option.expiration_key())
+}
+
+/// contract with the latest expiration, the first one on ties, `None` for an empty slice
+pub fn furthest_expiration(options: &[OptionData]) -> Option<&OptionData> {
+    options
+        .iter()
+        .rev()
+        .max_by_key(|option| option.expiration_key())
+}
+
+/// the standard monthly expiration of the given month, the third Friday
+///
+/// Returns [Error::MonthOutOfRange] for months outside of 1 to 12 and [Error::YearOutOfRange]
+/// for years a [NaiveDate] cannot hold.
+pub fn standard_monthly_expiration(year: i32, month: i32) -> Result<NaiveDate, Error> {
+    check_ymd(year, month, 15)?;
+    // the third Friday is the only Friday from the 15th to the 21st
+    let fifteenth = NaiveDate::from_ymd_opt(year, month as u32, 15).ok_or(Error::YearOutOfRange)?;
+    let days_to_friday =
+        (7 + Weekday::Fri.num_days_from_monday() - fifteenth.weekday().num_days_from_monday()) % 7;
+    Ok(fifteenth + Duration::days(days_to_friday as i64))
+}
+
+/// number of strikes `contract` lies out of the money in `chain`, negative if in the money
+///
+/// Only the distinct strikes of chain contracts with the same symbol and expiration are
+/// counted. The strike nearest to `underlying`, the lower one on ties, has rank 0; higher
+/// strikes are out of the money for calls and lower strikes for puts. Returns `None` if
+/// `contract` is not part of `chain`.
+pub fn strike_rank(contract: &OptionData, underlying: f64, chain: &[OptionData]) -> Option<i32> {
+    if !chain.iter().any(|option| option.semantically_eq(contract)) {
+        return None;
+    }
+    let mut strikes: Vec<Strike> = chain
+        .iter()
+        .filter(|option| {
+            option.symbol == contract.symbol && option.expiration_key() == contract.expiration_key()
+        })
+        .map(|option| option.nearest_strike())
+        .collect();
+    strikes.sort_unstable();
+    strikes.dedup();
+
+    let underlying = Strike::nearest(underlying);
+    let at_the_money =
+        (0..strikes.len()).min_by_key(|&i| (strikes[i].0 - underlying.0).abs())? as i32;
+    let position = strikes.binary_search(&contract.nearest_strike()).ok()? as i32;
+    match contract.contract_type {
+        ContractType::Call => Some(position - at_the_money),
+        ContractType::Put => Some(at_the_money - position),
+    }
+}
+
+/// removes contracts which are semantically equal to an earlier one, see [OptionData::semantically_eq]
+///
+/// The first occurrence is kept in its original position, with the strike rounded to the
+/// thousandths of the OSI price field.
+pub fn dedup_contracts(contracts: Vec<OptionData>) -> Vec<OptionData> {
+    let mut seen = HashSet::new();
+    contracts
+        .into_iter()
+        .filter(|option| seen.insert(option.cache_key()))
+        .map(|mut option| {
+            option.strike_price = option.nearest_strike().as_f64();
+            option
+        })
+        .collect()
+}
+
+/// infers the listed strike increment of a chain as the greatest common divisor of the strike differences
+///
+/// Returns `None` if there are fewer than two distinct strikes.
+pub fn infer_strike_increment(contracts: &[OptionData]) -> Option<f64> {
+    let mut strikes: Vec<i64> = contracts
+        .iter()
+        .map(|option| option.nearest_strike().0)
+        .collect();
+    strikes.sort_unstable();
+    strikes.dedup();
+
+    strikes
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .fold(None, |increment, difference| match increment {
+            None => Some(difference),
+            Some(increment) => Some(gcd(increment, difference)),
+        })
+        .map(|increment| Strike(increment).as_f64())
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// checks that month and day form a valid date in the given year
+fn check_ymd(year: i32, month: i32, day: i32) -> Result<(), Error> {
+    if !(1..=12).contains(&month) {
+        return Err(Error::MonthOutOfRange);
+    }
+    if !is_day_in_month_and_year(year, month, day) {
+        return Err(Error::DayOutOfRange);
+    }
+    Ok(())
+}
+
+/// escapes a string for use inside a JSON string literal
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// splits off the `$` or `.` some vendors put in front of index symbols
+fn split_index_prefix(s: &str) -> (Option<char>, &str) {
+    match s.chars().next() {
+        Some(prefix @ '$') | Some(prefix @ '.') => (Some(prefix), &s[1..]),
+        _ => (None, s),
+    }
+}
+
+/// matches `text` against `pattern`, returning [Error::NoResult] if it does not match
+fn captures<'t>(pattern: &str, text: &'t str) -> Result<Captures<'t>, Error> {
+    let re = match Regex::new(pattern) {
+        Ok(r) => r,
+        Err(e) => return Err(Error::RegexError(e.to_string())),
+    };
+    captures_compiled(&re, text)
+}
+
+/// like [captures] with a regex compiled by the caller, e.g. once for many lines
+fn captures_compiled<'t>(re: &Regex, text: &'t str) -> Result<Captures<'t>, Error> {
+    match re.captures(text) {
+        Ok(Some(cap)) => Ok(cap),
+        Ok(None) => Err(Error::NoResult),
+        Err(e) => Err(Error::RegexError(e.to_string())),
+    }
+}
+
+/// maps the contract letters accepted by the regexes to [ContractType]
+fn contract_type_from_letter(letter: &str) -> ContractType {
+    match letter {
+        "P" | "p" => ContractType::Put,
+        "C" | "c" => ContractType::Call,
+        _ => panic!(),
+    }
+}
+
+/// the year ending in `two_digit_year` from 50 years before to 49 years after `reference_year`
+fn closest_year(two_digit_year: i32, reference_year: i32) -> i32 {
+    let earliest = reference_year - 50;
+    earliest + (two_digit_year - earliest).rem_euclid(100)
+}
+
+/// parses a string consisting only of ASCII digits
+fn parse_digits(s: &str) -> Option<i32> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// parses a strike matched by the decimal price groups, dropping thousands separators like in `1,250.00`
+fn parse_decimal_strike(price: &str) -> f64 {
+    price.replace(',', "").parse().unwrap()
+}
+
+/// parses a byte slice consisting only of ASCII digits, at most 9 of them
+fn parse_ascii_digits(bytes: &[u8]) -> Option<i32> {
+    if bytes.is_empty() || bytes.len() > 9 {
+        return None;
+    }
+    bytes.iter().try_fold(0, |value: i32, &b| {
+        if b.is_ascii_digit() {
+            Some(value * 10 + (b - b'0') as i32)
+        } else {
+            None
+        }
+    })
+}
+
+/// the standard monthly expiration is the third Friday, which always falls on the 15th to 21st
+fn is_third_friday(date: NaiveDate) -> bool {
+    date.weekday() == Weekday::Fri && (15..=21).contains(&date.day())
 }
 
 /// leap year is every 4 years but not every 100 still every 400
 fn is_leap_year(year: i32) -> bool {
-    return (year % 4 == 0 && !(year % 100 == 0)) || year % 400 == 0;
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
 }
 
 const MONTH_WITH_31_DAYS: [i32; 7] = [1, 3, 5, 7, 8, 10, 12];
 /// checks if the day of month fits the month and year
 fn is_day_in_month_and_year(year: i32, month: i32, day: i32) -> bool {
-    return day > 0
+    day > 0
         && ((month == 2 && (day <= 28 || day == 29 && is_leap_year(year)))
-            || (month != 2 && (day <= 30 || day == 31 && MONTH_WITH_31_DAYS.contains(&month))));
+            || (month != 2 && (day <= 30 || day == 31 && MONTH_WITH_31_DAYS.contains(&month))))
 }
 
 #[cfg(test)]
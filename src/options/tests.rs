@@ -1,14 +1,27 @@
-use crate::options::{is_day_in_month_and_year, is_leap_year, ContractType, OptionData};
+use chrono::{NaiveDate, NaiveTime};
+
+use std::{convert::TryFrom, io::Cursor, str::FromStr};
+
+use crate::options::{
+    dedup_contracts, escape_json, furthest_expiration, infer_strike_increment,
+    is_day_in_month_and_year, is_leap_year, is_third_friday, reformat, soonest_expiration,
+    standard_monthly_expiration, strike_rank, ContractType, DateFormat, Error, ExpirationCycle,
+    FieldError, Format, Month3Letter, OptionData, OsiField, Strike, Symbol,
+};
 
 #[test]
 fn osi_well_formated() {
     let apple_01nov13_call_470 = OptionData {
-        strike_price: 470 as f64,
+        strike_price: 470_f64,
         contract_type: ContractType::Call,
-        symbol: "AAPL".to_string(),
+        symbol: Symbol::new("AAPL").unwrap(),
         expiration_year: 2013,
         expiration_month: 11,
         expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
     };
 
     assert_eq!(
@@ -20,12 +33,16 @@ fn osi_well_formated() {
 #[test]
 fn osi_symbol_padding_wrong() {
     let apple_01nov13_call_470 = OptionData {
-        strike_price: 470 as f64,
+        strike_price: 470_f64,
         contract_type: ContractType::Call,
-        symbol: "AAPL".to_string(),
+        symbol: Symbol::new("AAPL").unwrap(),
         expiration_year: 2013,
         expiration_month: 11,
         expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
     };
 
     assert_eq!(
@@ -41,12 +58,16 @@ fn osi_symbol_padding_wrong() {
 #[test]
 fn osi_contract_type_small() {
     let apple_01nov13_call_470 = OptionData {
-        strike_price: 470 as f64,
+        strike_price: 470_f64,
         contract_type: ContractType::Call,
-        symbol: "AAPL".to_string(),
+        symbol: Symbol::new("AAPL").unwrap(),
         expiration_year: 2013,
         expiration_month: 11,
         expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
     };
 
     assert_eq!(
@@ -58,12 +79,16 @@ fn osi_contract_type_small() {
 #[test]
 fn osi_formatting() {
     let apple_01nov13_call_470 = OptionData {
-        strike_price: 470 as f64,
+        strike_price: 470_f64,
         contract_type: ContractType::Call,
-        symbol: "AAPL".to_string(),
+        symbol: Symbol::new("AAPL").unwrap(),
         expiration_year: 2013,
         expiration_month: 11,
         expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
     };
 
     assert_eq!(
@@ -72,15 +97,27 @@ fn osi_formatting() {
     );
 }
 
+#[test]
+fn osi_invalid_date() {
+    assert_eq!(
+        OptionData::parse_osi("AAPL  130230C00470000"),
+        Err(Error::DayOutOfRange)
+    );
+}
+
 #[test]
 fn parse_ib_activity_statement_trades_symbol() {
     let apple_01nov13_call_470 = OptionData {
-        strike_price: 470 as f64,
+        strike_price: 470_f64,
         contract_type: ContractType::Call,
-        symbol: "AAPL".to_string(),
+        symbol: Symbol::new("AAPL").unwrap(),
         expiration_year: 2013,
         expiration_month: 11,
         expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
     };
 
     let apple_01nov13_call_470_parsed =
@@ -95,12 +132,16 @@ fn parse_ib_activity_statement_trades_symbol() {
 #[test]
 fn schwab_formatting() {
     let apple_01nov13_call_470 = OptionData {
-        strike_price: 470 as f64,
+        strike_price: 470_f64,
         contract_type: ContractType::Call,
-        symbol: "AAPL".to_string(),
+        symbol: Symbol::new("AAPL").unwrap(),
         expiration_year: 2013,
         expiration_month: 11,
         expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
     };
 
     assert_eq!(
@@ -111,16 +152,1440 @@ fn schwab_formatting() {
 
 #[test]
 fn test_is_leap_year() {
-    assert_eq!(true, is_leap_year(2000));
-    assert_eq!(true, is_leap_year(2004));
-    assert_eq!(false, is_leap_year(2100));
-    assert_eq!(false, is_leap_year(2021));
+    assert!(is_leap_year(2000));
+    assert!(is_leap_year(2004));
+    assert!(!is_leap_year(2100));
+    assert!(!is_leap_year(2021));
 }
 #[test]
 fn test_day_in_month() {
-    assert_eq!(true, is_day_in_month_and_year(2000, 2, 29));
-    assert_eq!(false, is_day_in_month_and_year(2001, 2, 29));
-    assert_eq!(false, is_day_in_month_and_year(2000, 2, 30));
-    assert_eq!(false, is_day_in_month_and_year(2000, 4, 31));
-    assert_eq!(true, is_day_in_month_and_year(2001, 8, 31));
+    assert!(is_day_in_month_and_year(2000, 2, 29));
+    assert!(!is_day_in_month_and_year(2001, 2, 29));
+    assert!(!is_day_in_month_and_year(2000, 2, 30));
+    assert!(!is_day_in_month_and_year(2000, 4, 31));
+    assert!(is_day_in_month_and_year(2001, 8, 31));
+}
+
+#[test]
+fn expiration_cycle() {
+    let today = NaiveDate::from_ymd_opt(2021, 5, 1).unwrap();
+
+    let weekly = OptionData::parse_osi("KO    210507C00050000").unwrap();
+    assert_eq!(ExpirationCycle::Weekly, weekly.expiration_cycle(today));
+
+    let monthly = OptionData::parse_osi("KO    210521C00050000").unwrap();
+    assert_eq!(ExpirationCycle::Monthly, monthly.expiration_cycle(today));
+
+    let quarterly = OptionData::parse_osi("KO    210618C00050000").unwrap();
+    assert_eq!(
+        ExpirationCycle::Quarterly,
+        quarterly.expiration_cycle(today)
+    );
+
+    let leaps = OptionData::parse_osi("KO    230120C00050000").unwrap();
+    assert_eq!(ExpirationCycle::Leaps, leaps.expiration_cycle(today));
+}
+
+#[test]
+fn underlying_symbol_of_adjusted_root() {
+    let adjusted = OptionData::parse_osi("AAPL1 131101C00470000").unwrap();
+    assert_eq!("AAPL1", adjusted.option_root());
+    assert_eq!("AAPL", adjusted.underlying_symbol());
+
+    let standard = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    assert_eq!("AAPL", standard.option_root());
+    assert_eq!("AAPL", standard.underlying_symbol());
+}
+
+#[test]
+fn diagnose_osi_bad_month() {
+    assert_eq!(
+        OptionData::diagnose_osi("AAPL  131301C00470000"),
+        Err(vec![FieldError {
+            field: OsiField::Month,
+            value: "13".to_string()
+        }])
+    );
+}
+
+#[test]
+fn diagnose_osi_valid() {
+    assert_eq!(
+        OptionData::diagnose_osi("AAPL  131101C00470000"),
+        Ok(OptionData::parse_osi("AAPL  131101C00470000").unwrap())
+    );
+}
+
+#[test]
+fn osi_formatting_preserving_case() {
+    let lowercase = OptionData::parse_osi("AAPL  131101c00470000").unwrap();
+    assert_eq!(
+        "AAPL  131101c00470000",
+        lowercase.to_osi_string_preserving_case()
+    );
+    assert_eq!("AAPL  131101C00470000", lowercase.to_osi_string());
+
+    let uppercase = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    assert_eq!(
+        "AAPL  131101C00470000",
+        uppercase.to_osi_string_preserving_case()
+    );
+}
+
+#[test]
+fn intrinsic_and_extrinsic_value() {
+    let call = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    assert_eq!(30.0, call.intrinsic_value(500.0));
+    assert_eq!(0.0, call.intrinsic_value(450.0));
+    // in the money: extrinsic is the time premium on top of intrinsic
+    assert_eq!(5.0, call.extrinsic_value(35.0, 500.0));
+    // out of the money: extrinsic is the full price
+    assert_eq!(4.5, call.extrinsic_value(4.5, 450.0));
+
+    let put = OptionData::parse_osi("AAPL  131101P00470000").unwrap();
+    assert_eq!(20.0, put.intrinsic_value(450.0));
+    assert_eq!(2.0, put.extrinsic_value(22.0, 450.0));
+    assert_eq!(0.0, put.extrinsic_value(19.0, 450.0));
+}
+
+#[test]
+fn parse_spaced() {
+    let apple_01nov13_call_470 = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+
+    assert_eq!(
+        OptionData::parse_spaced("AAPL 131101 C 470.00").unwrap(),
+        apple_01nov13_call_470
+    );
+    assert_eq!(
+        OptionData::parse_spaced("AAPL 130231 C 470.00"),
+        Err(Error::DayOutOfRange)
+    );
+}
+
+#[test]
+fn spaced_formatting() {
+    let apple_01nov13_call_470 = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+
+    assert_eq!(
+        "AAPL 131101 C 470.00",
+        apple_01nov13_call_470.to_spaced_string()
+    );
+}
+
+#[test]
+fn osi_formatting_year_range() {
+    let apple_2030 = OptionData {
+        strike_price: 470_f64,
+        contract_type: ContractType::Call,
+        symbol: Symbol::new("AAPL").unwrap(),
+        expiration_year: 2030,
+        expiration_month: 11,
+        expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
+    };
+    assert_eq!(
+        Ok("AAPL  301101C00470000".to_string()),
+        apple_2030.try_to_osi_string()
+    );
+
+    let apple_2105 = OptionData {
+        strike_price: 470_f64,
+        contract_type: ContractType::Call,
+        symbol: Symbol::new("AAPL").unwrap(),
+        expiration_year: 2105,
+        expiration_month: 11,
+        expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
+    };
+    assert_eq!(Err(Error::YearOutOfRange), apple_2105.try_to_osi_string());
+}
+
+#[test]
+fn parse_ib_reader() {
+    let statement = "Trades\nAAPL 01NOV13 470.0 C\r\nKO 28MAY21 32.01 P\nKO 30FEB21 32.01 P\n";
+
+    let parsed: Vec<Result<OptionData, Error>> =
+        OptionData::parse_ib_reader(Cursor::new(statement)).collect();
+
+    assert_eq!(3, parsed.len());
+    assert_eq!(
+        parsed[0],
+        Ok(OptionData::parse_osi("AAPL  131101C00470000").unwrap())
+    );
+    assert_eq!(
+        parsed[1],
+        Ok(OptionData::parse_osi("KO    210528P00032010").unwrap())
+    );
+    assert_eq!(parsed[2], Err(Error::DayOutOfRange));
+}
+
+#[test]
+fn contract_type_u8_round_trip() {
+    assert_eq!(0, ContractType::Call.as_u8());
+    assert_eq!(1, ContractType::Put.as_u8());
+    assert_eq!(
+        Some(ContractType::Call),
+        ContractType::from_u8(ContractType::Call.as_u8())
+    );
+    assert_eq!(
+        Some(ContractType::Put),
+        ContractType::from_u8(ContractType::Put.as_u8())
+    );
+    assert_eq!(None, ContractType::from_u8(2));
+}
+
+#[test]
+fn dated_formatting() {
+    let apple_01nov13_call_470 = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+
+    assert_eq!(
+        "AAPL 11/01/2013 470.00 C",
+        apple_01nov13_call_470.to_dated_string(DateFormat::UsSlash)
+    );
+    assert_eq!(
+        "AAPL 01/11/2013 470.00 C",
+        apple_01nov13_call_470.to_dated_string(DateFormat::EuSlash)
+    );
+    assert_eq!(
+        "AAPL 2013-11-01 470.00 C",
+        apple_01nov13_call_470.to_dated_string(DateFormat::Iso)
+    );
+}
+
+#[test]
+fn semantically_eq() {
+    let upper = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    let lower = OptionData::parse_osi("aapl  131101C00470000").unwrap();
+    assert!(upper.semantically_eq(&lower));
+
+    let noisy_strike = OptionData {
+        strike_price: 470.000_000_1,
+        contract_type: ContractType::Call,
+        symbol: Symbol::new("aapl").unwrap(),
+        expiration_year: 2013,
+        expiration_month: 11,
+        expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
+    };
+    assert!(upper != noisy_strike);
+    assert!(upper.semantically_eq(&noisy_strike));
+
+    let put = OptionData::parse_osi("AAPL  131101P00470000").unwrap();
+    assert!(!upper.semantically_eq(&put));
+}
+
+#[test]
+fn parse_osi_with_verify() {
+    let five_dollar_increments = |option: &OptionData| option.strike_price % 5.0 == 0.0;
+
+    assert_eq!(
+        OptionData::parse_osi_with_verify("AAPL  131101C00470000", five_dollar_increments),
+        Ok(OptionData::parse_osi("AAPL  131101C00470000").unwrap())
+    );
+    assert_eq!(
+        OptionData::parse_osi_with_verify("AAPL  131101C00472000", five_dollar_increments),
+        Err(Error::ChecksumError)
+    );
+}
+
+#[test]
+fn parse_schwab() {
+    assert_eq!(
+        OptionData::parse_schwab("AAPL 11/01/2013 470.00 C").unwrap(),
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap()
+    );
+    assert_eq!(
+        OptionData::parse_schwab("AAPL 02/29/2013 470.00 C"),
+        Err(Error::DayOutOfRange)
+    );
+}
+
+#[test]
+fn cache_key() {
+    let from_osi = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    let from_schwab = OptionData::parse_schwab("AAPL 11/01/2013 470.00 C").unwrap();
+
+    assert_eq!("AAPL|2013-11-01|C|470000", from_osi.cache_key());
+    assert_eq!(from_osi.cache_key(), from_schwab.cache_key());
+}
+
+#[test]
+fn ib_tws_round_trip() {
+    let apple_01nov13_call_470 = OptionData::parse_ib_tws("AAPL 20131101 470 C").unwrap();
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap(),
+        apple_01nov13_call_470
+    );
+    assert_eq!(
+        "AAPL 20131101 470 C",
+        apple_01nov13_call_470.to_ib_tws_string()
+    );
+
+    let ko_28may21_put_32_01 = OptionData::parse_ib_tws("KO 20210528 32.01 P").unwrap();
+    assert_eq!(
+        "KO 20210528 32.01 P",
+        ko_28may21_put_32_01.to_ib_tws_string()
+    );
+}
+
+#[test]
+fn ib_tws_invalid_date() {
+    assert_eq!(
+        OptionData::parse_ib_tws("AAPL 20130431 470 C"),
+        Err(Error::DayOutOfRange)
+    );
+}
+
+#[test]
+fn soonest_and_furthest_expiration() {
+    let chain = vec![
+        OptionData::parse_osi("AAPL  131115C00470000").unwrap(),
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap(),
+        OptionData::parse_osi("AAPL  140117C00470000").unwrap(),
+        OptionData::parse_osi("AAPL  131108C00470000").unwrap(),
+    ];
+
+    assert_eq!(Some(&chain[1]), soonest_expiration(&chain));
+    assert_eq!(Some(&chain[2]), furthest_expiration(&chain));
+    assert_eq!(None, soonest_expiration(&[]));
+    assert_eq!(None, furthest_expiration(&[]));
+}
+
+#[test]
+fn validate() {
+    assert_eq!(
+        Ok(()),
+        OptionData::parse_osi("AAPL  131101C00470000")
+            .unwrap()
+            .validate()
+    );
+
+    let month_out_of_range = OptionData {
+        strike_price: 470_f64,
+        contract_type: ContractType::Call,
+        symbol: Symbol::new("AAPL").unwrap(),
+        expiration_year: 2013,
+        expiration_month: 13,
+        expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
+    };
+    assert_eq!(Err(Error::MonthOutOfRange), month_out_of_range.validate());
+
+    let bad_date = OptionData {
+        strike_price: 470_f64,
+        contract_type: ContractType::Call,
+        symbol: Symbol::new("AAPL").unwrap(),
+        expiration_year: 2013,
+        expiration_month: 2,
+        expiration_day: 30,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
+    };
+    assert_eq!(Err(Error::DayOutOfRange), bad_date.validate());
+
+    let bad_strike = OptionData {
+        strike_price: 470.0001,
+        contract_type: ContractType::Call,
+        symbol: Symbol::new("AAPL").unwrap(),
+        expiration_year: 2013,
+        expiration_month: 11,
+        expiration_day: 1,
+        contract_letter_uppercase: true,
+        index_prefix: None,
+        exchange: None,
+        original_root: None,
+    };
+    assert_eq!(Err(Error::StrikeOutOfRange), bad_strike.validate());
+}
+
+#[test]
+fn parse_retail() {
+    assert_eq!(
+        OptionData::parse_retail("AAPL 2013-11-01 call 470.00").unwrap(),
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap()
+    );
+    assert_eq!(
+        OptionData::parse_retail("KO 2021-05-28 put 32.50").unwrap(),
+        OptionData::parse_osi("KO    210528P00032500").unwrap()
+    );
+    assert_eq!(
+        OptionData::parse_retail("KO 2021-02-29 put 32.50"),
+        Err(Error::DayOutOfRange)
+    );
+}
+
+#[test]
+fn occ_settlement_saturday() {
+    let third_friday = OptionData::parse_osi("AAPL  131115C00470000").unwrap();
+    assert_eq!(
+        NaiveDate::from_ymd_opt(2013, 11, 16).unwrap(),
+        third_friday.occ_settlement_saturday()
+    );
+
+    let legacy = OptionData::parse_osi_with_legacy_saturday("AAPL  131116C00470000", true).unwrap();
+    assert_eq!(third_friday, legacy);
+    assert_eq!(
+        NaiveDate::from_ymd_opt(2013, 11, 16).unwrap(),
+        legacy.occ_settlement_saturday()
+    );
+
+    let unshifted =
+        OptionData::parse_osi_with_legacy_saturday("AAPL  131116C00470000", false).unwrap();
+    assert_eq!(16, unshifted.get_expiration_day());
+    assert_eq!(
+        NaiveDate::from_ymd_opt(2013, 11, 16).unwrap(),
+        unshifted.occ_settlement_saturday()
+    );
+}
+
+#[test]
+fn osi_length_out_of_range() {
+    assert_eq!(
+        OptionData::parse_osi("AAPL131101C0047"),
+        Err(Error::LengthOutOfRange { len: 15 })
+    );
+    assert_eq!(
+        OptionData::parse_osi("AAPL    131101C00470000"),
+        Err(Error::LengthOutOfRange { len: 23 })
+    );
+    assert_eq!(
+        OptionData::parse_osi("THIS IS NOT AN OSI!!"),
+        Err(Error::NoResult)
+    );
+}
+
+#[test]
+fn json_formatting() {
+    let apple_01nov13_call_470 = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    assert_eq!(
+        r#"{"symbol":"AAPL","expiration":"2013-11-01","strike":470.0,"type":"C"}"#,
+        apple_01nov13_call_470.to_json()
+    );
+    assert_eq!(
+        r#"{"symbol":"KO","expiration":"2021-05-28","strike":32.01,"type":"P"}"#,
+        OptionData::parse_osi("KO    210528P00032010")
+            .unwrap()
+            .to_json()
+    );
+    assert_eq!(r#"K\"O\\\n"#, escape_json("K\"O\\\n"));
+}
+
+#[test]
+fn parse_surrounding_whitespace() {
+    let apple_01nov13_call_470 = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131101C00470000\r\n").unwrap(),
+        apple_01nov13_call_470
+    );
+    assert_eq!(
+        OptionData::parse_osi("  AAPL  131101C00470000").unwrap(),
+        apple_01nov13_call_470
+    );
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131101C00470000   ").unwrap(),
+        apple_01nov13_call_470
+    );
+    assert_eq!(
+        OptionData::parse_ib_activity_statement_trades_symbol(" AAPL 01NOV13 470.0 C\r\n").unwrap(),
+        apple_01nov13_call_470
+    );
+    assert_eq!(
+        OptionData::parse_schwab("AAPL 11/01/2013 470.00 C\r\n").unwrap(),
+        apple_01nov13_call_470
+    );
+}
+
+#[test]
+fn parse_ib_activity_statement_trades_symbol_locale() {
+    let ko_28may21_call_32_01 = OptionData::parse_osi("KO    210528C00032010").unwrap();
+
+    assert_eq!(
+        OptionData::parse_ib_activity_statement_trades_symbol_locale("KO 28MAY21 32,01 C", ',')
+            .unwrap(),
+        ko_28may21_call_32_01
+    );
+    assert_eq!(
+        OptionData::parse_ib_activity_statement_trades_symbol_locale("KO 28MAY21 32.01 C", '.')
+            .unwrap(),
+        ko_28may21_call_32_01
+    );
+    assert_eq!(
+        OptionData::parse_ib_activity_statement_trades_symbol("KO 28MAY21 32,01 C"),
+        Err(Error::NoResult)
+    );
+}
+
+#[test]
+fn canonical_osi() {
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        OptionData::canonical_osi("AAPL 01NOV13 470.0 C")
+    );
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        OptionData::canonical_osi("AAPL 11/01/2013 470.00 C")
+    );
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        OptionData::canonical_osi("AAPL131101C00470000")
+    );
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        OptionData::canonical_osi("AAPL 131101 C 00470000")
+    );
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        OptionData::canonical_osi("AAPL  20131101C00470000")
+    );
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        OptionData::canonical_osi("AAPL 131101 C 470.00")
+    );
+    assert_eq!(
+        Err(Error::DayOutOfRange),
+        OptionData::canonical_osi("AAPL 02/30/2013 470.00 C")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::canonical_osi("not an option")
+    );
+}
+
+#[test]
+fn osi_index_prefix() {
+    let spx = OptionData::parse_osi(".SPX  231117C04500000").unwrap();
+    assert!(spx.is_index());
+    assert_eq!(spx.symbol, "SPX");
+    assert_eq!(4500.0, spx.strike_price);
+    assert_eq!("SPX   231117C04500000", spx.to_osi_string());
+    assert_eq!(".SPX  231117C04500000", spx.to_index_osi_string());
+
+    let spx_dollar = OptionData::parse_osi("$SPX  231117C04500000").unwrap();
+    assert!(spx_dollar.is_index());
+    assert_eq!("$SPX  231117C04500000", spx_dollar.to_index_osi_string());
+
+    let plain = OptionData::parse_osi("SPX   231117C04500000").unwrap();
+    assert!(!plain.is_index());
+    assert_eq!(plain, spx);
+}
+
+#[test]
+fn infer_strike_increment_of_chain() {
+    let five_dollar_chain: Vec<OptionData> = ["00460000", "00470000", "00465000", "00475000"]
+        .iter()
+        .map(|price| OptionData::parse_osi(&format!("AAPL  131101C{}", price)).unwrap())
+        .collect();
+    assert_eq!(Some(5.0), infer_strike_increment(&five_dollar_chain));
+
+    let mixed_chain: Vec<OptionData> = ["00010000", "00011000", "00012000", "00012500", "00015000"]
+        .iter()
+        .map(|price| OptionData::parse_osi(&format!("KO    210528C{}", price)).unwrap())
+        .collect();
+    assert_eq!(Some(0.5), infer_strike_increment(&mixed_chain));
+
+    let single_strike = vec![
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap(),
+        OptionData::parse_osi("AAPL  131101P00470000").unwrap(),
+    ];
+    assert_eq!(None, infer_strike_increment(&single_strike));
+    assert_eq!(None, infer_strike_increment(&[]));
+}
+
+#[test]
+fn break_even() {
+    let call = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    assert_eq!(482.5, call.break_even(12.5));
+
+    let put = OptionData::parse_osi("AAPL  131101P00470000").unwrap();
+    assert_eq!(457.5, put.break_even(12.5));
+}
+
+#[test]
+fn try_from_tuple() {
+    let expiration = NaiveDate::from_ymd_opt(2013, 11, 1).unwrap();
+
+    assert_eq!(
+        OptionData::try_from(("AAPL", expiration, 470.0, ContractType::Call)),
+        Ok(OptionData::parse_osi("AAPL  131101C00470000").unwrap())
+    );
+    assert_eq!(
+        OptionData::try_from(("AAPL", expiration, 470.0001, ContractType::Call)),
+        Err(Error::StrikeOutOfRange)
+    );
+    assert_eq!(
+        OptionData::try_from(("AAPL", expiration, -5.0, ContractType::Call)),
+        Err(Error::StrikeOutOfRange)
+    );
+    assert_eq!(
+        OptionData::try_from(("AAPL TOO LONG", expiration, 470.0, ContractType::Call)),
+        Err(Error::InvalidSymbol)
+    );
+}
+
+#[test]
+fn osi_date_boundaries() {
+    // month
+    assert_eq!(
+        OptionData::parse_osi("AAPL  130001C00470000"),
+        Err(Error::NoResult)
+    );
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131301C00470000"),
+        Err(Error::NoResult)
+    );
+    assert!(OptionData::parse_osi("AAPL  130101C00470000").is_ok());
+    assert!(OptionData::parse_osi("AAPL  131201C00470000").is_ok());
+
+    // day
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131100C00470000"),
+        Err(Error::NoResult)
+    );
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131232C00470000"),
+        Err(Error::NoResult)
+    );
+    assert_eq!(
+        OptionData::parse_osi("AAPL  131131C00470000"),
+        Err(Error::DayOutOfRange)
+    );
+    assert!(OptionData::parse_osi("AAPL  131231C00470000").is_ok());
+    assert!(OptionData::parse_osi("AAPL  160229C00470000").is_ok());
+    assert_eq!(
+        OptionData::parse_osi("AAPL  150229C00470000"),
+        Err(Error::DayOutOfRange)
+    );
+
+    // year
+    assert_eq!(
+        2000,
+        OptionData::parse_osi("AAPL  000103C00470000")
+            .unwrap()
+            .get_expiration_year()
+    );
+    assert_eq!(
+        2099,
+        OptionData::parse_osi("AAPL  991231C00470000")
+            .unwrap()
+            .get_expiration_year()
+    );
+}
+
+#[test]
+fn ib_and_tos_round_trip() {
+    let ko_28may21_call_32_01 = OptionData::parse_osi("KO    210528C00032010").unwrap();
+
+    assert_eq!(
+        "KO 28MAY21 32.01 C",
+        ko_28may21_call_32_01.to_ib_activity_statement_string()
+    );
+    assert_eq!(".KO210528C32.01", ko_28may21_call_32_01.to_tos_string());
+    assert_eq!(
+        OptionData::parse_tos(".KO210528C32.01").unwrap(),
+        ko_28may21_call_32_01
+    );
+}
+
+#[test]
+fn reformat_between_formats() {
+    assert_eq!(
+        Ok("AAPL 11/01/2013 470.00 C".to_string()),
+        reformat("AAPL  131101C00470000", Format::Osi, Format::Schwab)
+    );
+    assert_eq!(
+        Ok("AAPL  131101C00470000".to_string()),
+        reformat("AAPL 11/01/2013 470.00 C", Format::Schwab, Format::Osi)
+    );
+    assert_eq!(
+        Ok("AAPL131101C00470000".to_string()),
+        reformat("AAPL 01NOV13 470.0 C", Format::Ib, Format::Yahoo)
+    );
+    assert_eq!(
+        Ok(".AAPL131101C470".to_string()),
+        reformat("AAPL 01NOV13 470.0 C", Format::Ib, Format::Tos)
+    );
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        reformat("AAPL 11/01/2113 470.00 C", Format::Schwab, Format::Osi)
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        reformat("AAPL  131101C00470000", Format::Schwab, Format::Osi)
+    );
+}
+
+#[test]
+fn osi_exchange_suffix() {
+    let with_exchange = OptionData::parse_osi_with_exchange("AAPL  131101C00470000:CBOE").unwrap();
+    assert_eq!(Some("CBOE".to_string()), with_exchange.exchange);
+    assert_eq!("AAPL  131101C00470000", with_exchange.to_osi_string());
+    assert_eq!(
+        "AAPL  131101C00470000:CBOE",
+        with_exchange.to_osi_string_with_exchange()
+    );
+
+    let without_exchange = OptionData::parse_osi_with_exchange("AAPL  131101C00470000").unwrap();
+    assert_eq!(None, without_exchange.exchange);
+    assert_eq!(
+        "AAPL  131101C00470000",
+        without_exchange.to_osi_string_with_exchange()
+    );
+    assert_eq!(with_exchange, without_exchange);
+
+    assert_eq!(
+        OptionData::parse_osi_with_exchange("AAPL  131101C00470000:"),
+        Err(Error::NoResult)
+    );
+}
+
+#[test]
+fn expiration_datetime() {
+    let apple_01nov13_call_470 = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    let expiration = NaiveDate::from_ymd_opt(2013, 11, 1).unwrap();
+
+    let pm_close = NaiveTime::from_hms_opt(16, 0, 0).unwrap();
+    assert_eq!(
+        expiration.and_time(pm_close),
+        apple_01nov13_call_470.expiration_datetime(pm_close)
+    );
+
+    let am_open = NaiveTime::from_hms_opt(9, 30, 0).unwrap();
+    assert_eq!(
+        expiration.and_hms_opt(9, 30, 0).unwrap(),
+        apple_01nov13_call_470.expiration_datetime(am_open)
+    );
+}
+
+#[test]
+fn symbol_validation() {
+    assert_eq!("AAPL", &*Symbol::new("AAPL").unwrap());
+    assert_eq!(Symbol::new("aapl1").unwrap(), "AAPL1");
+    assert_eq!(Symbol::new("BRKB").unwrap().len(), 4);
+
+    assert_eq!(Err(Error::InvalidSymbol), Symbol::new(""));
+    assert_eq!(Err(Error::InvalidSymbol), Symbol::new("AAPL TOO LONG"));
+    assert_eq!(Err(Error::InvalidSymbol), Symbol::new("TOOLONG"));
+    assert_eq!(Err(Error::InvalidSymbol), Symbol::new("BRK.B"));
+    assert_eq!(Err(Error::InvalidSymbol), Symbol::new("A_B"));
+}
+
+#[test]
+fn osi_lowercase_symbol_is_uppercased() {
+    let apple = OptionData::parse_osi("aapl  131101C00470000").unwrap();
+    assert_eq!(apple.symbol, "AAPL");
+    assert_eq!("AAPL  131101C00470000", apple.to_osi_string());
+    assert_eq!(
+        OptionData::parse_osi("A_B   131101C00470000"),
+        Err(Error::InvalidSymbol)
+    );
+}
+
+#[test]
+fn osi_lengths() {
+    for osi in &[
+        "A     131101C00470000",
+        "AAPL  131101C00470000",
+        "GOOGL 131101P01000500",
+        "AAPL12131101C00470000",
+    ] {
+        let option = OptionData::parse_osi(osi).unwrap();
+        assert_eq!(option.to_osi_string().len(), option.osi_padded_len());
+        assert_eq!(
+            option.to_osi_string_no_symbol_padding().len(),
+            option.osi_unpadded_len()
+        );
+    }
+
+    // years outside of the two digit field change the length
+    let far = OptionData::parse_schwab("AAPL 11/01/2105 470.00 C").unwrap();
+    assert_eq!(far.to_osi_string().len(), far.osi_padded_len());
+    assert_eq!(
+        far.to_osi_string_no_symbol_padding().len(),
+        far.osi_unpadded_len()
+    );
+    let early = OptionData::parse_osi_4digit_year("AAPL  19981120C00470000").unwrap();
+    assert_eq!(early.to_osi_string().len(), early.osi_padded_len());
+}
+
+#[test]
+fn month_3_letter_round_trip() {
+    for month in 1..=12 {
+        let m = Month3Letter::from_number(month).unwrap();
+        assert_eq!(month, m as i32);
+        assert_eq!(3, m.to_str().len());
+        assert_eq!(Ok(m), Month3Letter::from_str(m.to_str()));
+    }
+    assert_eq!("MAY", Month3Letter::from_number(5).unwrap().to_str());
+    assert_eq!(None, Month3Letter::from_number(0));
+    assert_eq!(None, Month3Letter::from_number(13));
+}
+
+#[test]
+fn ib_activity_statement_unknown_month() {
+    assert_eq!(
+        OptionData::parse_ib_activity_statement_trades_symbol("KO 28XYZ21 32.01 C"),
+        Err(Error::MonthOutOfRange)
+    );
+}
+
+#[test]
+fn strip_weekly_marker() {
+    // 2013-11-08 is a Friday
+    let mut weekly = OptionData::parse_osi("AAPL2 131108C00470000").unwrap();
+    weekly.strip_weekly_marker();
+    assert_eq!("AAPL", weekly.option_root());
+    assert_eq!(Some("AAPL2"), weekly.original_root());
+    assert_eq!("AAPL  131108C00470000", weekly.to_osi_string());
+
+    // stripping happens only once
+    let mut double = OptionData::parse_osi("AAPL12131108C00470000").unwrap();
+    double.strip_weekly_marker();
+    double.strip_weekly_marker();
+    assert_eq!("AAPL1", double.option_root());
+    assert_eq!(Some("AAPL12"), double.original_root());
+
+    // 2013-11-09 is a Saturday
+    let mut saturday = OptionData::parse_osi("AAPL2 131109C00470000").unwrap();
+    saturday.strip_weekly_marker();
+    assert_eq!("AAPL2", saturday.option_root());
+    assert_eq!(None, saturday.original_root());
+
+    for root in ["AAPL", "AAPL7"].iter() {
+        let mut option = OptionData::parse_osi(&format!("{:<6}131108C00470000", root)).unwrap();
+        option.strip_weekly_marker();
+        assert_eq!(*root, option.option_root());
+        assert_eq!(None, option.original_root());
+    }
+}
+
+#[test]
+fn osi_strict_case() {
+    assert!(OptionData::parse_osi("AAPL  131101c00470000").is_ok());
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_strict_case("AAPL  131101c00470000")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_strict_case("AAPL  131101p00470000")
+    );
+
+    let call = OptionData::parse_osi_strict_case("AAPL  131101C00470000").unwrap();
+    assert_eq!(ContractType::Call, call.contract_type);
+    let put = OptionData::parse_osi_strict_case("AAPL  131101P00470000").unwrap();
+    assert_eq!(ContractType::Put, put.contract_type);
+}
+
+#[test]
+fn strike_ladder() {
+    let base = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    let ladder = OptionData::strike_ladder(&base, 450_f64, 500_f64, 5_f64).unwrap();
+    assert_eq!(11, ladder.len());
+    assert_eq!("AAPL  131101C00450000", ladder[0].to_osi_string());
+    assert_eq!("AAPL  131101C00500000", ladder[10].to_osi_string());
+    assert_eq!(Some(5_f64), infer_strike_increment(&ladder));
+
+    // the last step may stop short of `high`
+    let ladder = OptionData::strike_ladder(&base, 1_f64, 2_f64, 0.3).unwrap();
+    let strikes: Vec<f64> = ladder.iter().map(|option| option.strike_price).collect();
+    assert_eq!(vec![1_f64, 1.3, 1.6, 1.9], strikes);
+
+    assert!(OptionData::strike_ladder(&base, 500_f64, 450_f64, 5_f64)
+        .unwrap()
+        .is_empty());
+
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::strike_ladder(&base, 450_f64, 500_f64, 0_f64)
+    );
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::strike_ladder(&base, 450_f64, 500_f64, -5_f64)
+    );
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::strike_ladder(&base, 450_f64, 500_f64, 0.0001)
+    );
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::strike_ladder(&base, 450_f64, 100_000_f64, 5_f64)
+    );
+}
+
+#[test]
+fn compare_with_osi_string() {
+    let option = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    assert_eq!(option, "AAPL  131101C00470000");
+    assert!(option == *"AAPL  131101C00470000");
+
+    assert_ne!(option, "AAPL  131101P00470000");
+    assert_ne!(option, "AAPL131101C00470000");
+    assert_ne!(option, "aapl  131101c00470000");
+
+    // not the 2005 contract
+    let far = OptionData::parse_schwab("AAPL 11/01/2105 470.00 C").unwrap();
+    assert_ne!(far, "AAPL  051101C00470000");
+    assert_ne!(far, far.to_osi_string().as_str());
+}
+
+#[test]
+fn mini_options() {
+    assert!(OptionData::parse_osi("AAPL7 131101C00470000")
+        .unwrap()
+        .is_mini());
+    assert!(!OptionData::parse_osi("AAPL  131101C00470000")
+        .unwrap()
+        .is_mini());
+    assert!(!OptionData::parse_osi("AAPL1 131101C00470000")
+        .unwrap()
+        .is_mini());
+}
+
+#[test]
+fn opra_round_trip() {
+    let option = OptionData::parse_opra("AAPL 131101 C 00470000").unwrap();
+    assert_eq!(
+        option,
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap()
+    );
+    assert_eq!("AAPL 131101 C 00470000", option.to_opra_string());
+
+    let put = OptionData::parse_osi("SPX   141122P00019500").unwrap();
+    assert_eq!("SPX 141122 P 00019500", put.to_opra_string());
+    assert_eq!(put, OptionData::parse_opra(&put.to_opra_string()).unwrap());
+
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_opra("AAPL 131101 C 470.00")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_opra("AAPL  131101C00470000")
+    );
+}
+
+#[test]
+fn dedup_contracts_keeps_first_seen() {
+    let mut noisy = OptionData::parse_osi("aapl  131101C00470000").unwrap();
+    noisy.strike_price = 470.000_000_1;
+    let contracts = vec![
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap(),
+        OptionData::parse_osi("AAPL  131101P00470000").unwrap(),
+        noisy,
+    ];
+
+    let deduped = dedup_contracts(contracts);
+    assert_eq!(2, deduped.len());
+    assert_eq!("AAPL  131101C00470000", deduped[0].to_osi_string());
+    assert_eq!("AAPL  131101P00470000", deduped[1].to_osi_string());
+
+    let mut noisy_first = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    noisy_first.strike_price = 470.000_000_1;
+    let deduped = dedup_contracts(vec![noisy_first]);
+    assert_eq!(470_f64, deduped[0].strike_price);
+}
+
+#[test]
+fn osi_bytes_matches_osi() {
+    for osi in [
+        "AAPL  131101C00470000",
+        "SPX   141122P00019500",
+        "BRKB  230616c00300500",
+        "AAPL1 131101P00000500",
+        "GOOGL2240119C01500000",
+    ]
+    .iter()
+    {
+        let from_bytes = OptionData::parse_osi_bytes(osi.as_bytes()).unwrap();
+        let from_str = OptionData::parse_osi(osi).unwrap();
+        assert_eq!(from_str, from_bytes);
+        assert_eq!(from_str.to_osi_string(), from_bytes.to_osi_string());
+        assert_eq!(
+            from_str.to_osi_string_preserving_case(),
+            from_bytes.to_osi_string_preserving_case()
+        );
+    }
+}
+
+#[test]
+fn length_error_display() {
+    let error = OptionData::parse_osi_bytes(b"AAPL131101C00470000").unwrap_err();
+    assert_eq!(
+        "Supplied String has length 19 which the format does not allow",
+        error.to_string()
+    );
+}
+
+#[test]
+fn osi_bytes_errors() {
+    assert_eq!(
+        Err(Error::LengthOutOfRange { len: 19 }),
+        OptionData::parse_osi_bytes(b"AAPL131101C00470000")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_bytes(b"AAPL  131101X00470000")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_bytes(b"AAPL  1311O1C00470000")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_bytes(b"AAPL  131101C0047000-")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_bytes(b"AA PL 131101C00470000")
+    );
+    assert_eq!(
+        Err(Error::InvalidSymbol),
+        OptionData::parse_osi_bytes(b"      131101C00470000")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_bytes(b"AAP\xc3\xa9 131101C00470000")
+    );
+    assert_eq!(
+        Err(Error::MonthOutOfRange),
+        OptionData::parse_osi_bytes(b"AAPL  131301C00470000")
+    );
+    assert_eq!(
+        Err(Error::DayOutOfRange),
+        OptionData::parse_osi_bytes(b"AAPL  130230C00470000")
+    );
+}
+
+#[test]
+fn straddle_pair() {
+    for osi in ["AAPL  131101C00470000", "AAPL  131101P00470000"].iter() {
+        let (call, put) = OptionData::parse_osi(osi).unwrap().straddle_pair();
+        assert_eq!("AAPL  131101C00470000", call.to_osi_string());
+        assert_eq!("AAPL  131101P00470000", put.to_osi_string());
+        assert_eq!(call.symbol, put.symbol);
+        assert_eq!(call.get_expiration_date(), put.get_expiration_date());
+        assert_eq!(call.strike_price, put.strike_price);
+        assert_ne!(call.contract_type, put.contract_type);
+    }
+}
+
+#[test]
+fn osi_4digit_year_round_trip() {
+    let option = OptionData::parse_osi_4digit_year("AAPL  20131101C00470000").unwrap();
+    assert_eq!(
+        option,
+        OptionData::parse_osi("AAPL  131101C00470000").unwrap()
+    );
+    assert_eq!(
+        "AAPL  20131101C00470000",
+        option.to_osi_string_4digit_year()
+    );
+
+    let option = OptionData::parse_osi_4digit_year("SPX   20301220P00019500").unwrap();
+    assert_eq!(2030, option.get_expiration_year());
+    assert_eq!(
+        "SPX   20301220P00019500",
+        option.to_osi_string_4digit_year()
+    );
+
+    // no century pivot
+    let option = OptionData::parse_osi_4digit_year("AAPL  21131101C00470000").unwrap();
+    assert_eq!(2113, option.get_expiration_year());
+    assert_eq!(Err(Error::YearOutOfRange), option.try_to_osi_string());
+
+    assert_eq!(
+        Err(Error::DayOutOfRange),
+        OptionData::parse_osi_4digit_year("AAPL  20130230C00470000")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_osi_4digit_year("AAPL  131101C00470000")
+    );
+}
+
+#[test]
+fn strike_rank_around_the_money() {
+    let base = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+    let calls = OptionData::strike_ladder(&base, 460_f64, 480_f64, 5_f64).unwrap();
+    let puts = OptionData::strike_ladder(
+        &base.clone().with_contract_type(ContractType::Put),
+        460_f64,
+        480_f64,
+        5_f64,
+    )
+    .unwrap();
+    let chain: Vec<OptionData> = calls.iter().chain(puts.iter()).cloned().collect();
+
+    // strikes 460, 465, 470, 475, 480 with the underlying nearest to 470
+    let call_ranks: Vec<Option<i32>> = calls
+        .iter()
+        .map(|call| strike_rank(call, 471.2, &chain))
+        .collect();
+    assert_eq!(
+        vec![Some(-2), Some(-1), Some(0), Some(1), Some(2)],
+        call_ranks
+    );
+    let put_ranks: Vec<Option<i32>> = puts
+        .iter()
+        .map(|put| strike_rank(put, 471.2, &chain))
+        .collect();
+    assert_eq!(
+        vec![Some(2), Some(1), Some(0), Some(-1), Some(-2)],
+        put_ranks
+    );
+
+    // ties go to the lower strike, far away underlyings to the outermost strike
+    assert_eq!(Some(0), strike_rank(&calls[2], 472.5, &chain));
+    assert_eq!(Some(4), strike_rank(&calls[4], 100_f64, &chain));
+
+    let missing = OptionData::parse_osi("AAPL  131101C00490000").unwrap();
+    assert_eq!(None, strike_rank(&missing, 471.2, &chain));
+    let other_expiration = OptionData::parse_osi("AAPL  131108C00470000").unwrap();
+    assert_eq!(None, strike_rank(&other_expiration, 471.2, &chain));
+}
+
+#[test]
+fn standard_monthly_expirations() {
+    for &(year, month, day) in [
+        (2013, 11, 15),
+        (2013, 12, 20),
+        (2014, 1, 17),
+        (2021, 5, 21),
+        (2024, 2, 16),
+        (2026, 10, 16),
+    ]
+    .iter()
+    {
+        assert_eq!(
+            NaiveDate::from_ymd_opt(year, month as u32, day).unwrap(),
+            standard_monthly_expiration(year, month).unwrap()
+        );
+    }
+    for month in 1..=12 {
+        assert!(is_third_friday(
+            standard_monthly_expiration(2020, month).unwrap()
+        ));
+    }
+
+    assert_eq!(
+        Err(Error::MonthOutOfRange),
+        standard_monthly_expiration(2013, 0)
+    );
+    assert_eq!(
+        Err(Error::MonthOutOfRange),
+        standard_monthly_expiration(2013, 13)
+    );
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        standard_monthly_expiration(i32::MAX, 1)
+    );
+}
+
+#[test]
+fn strike_thousands_separators() {
+    let schwab = OptionData::parse_schwab("SPX 11/01/2013 1,250.00 C").unwrap();
+    assert_eq!(1250_f64, schwab.strike_price);
+    assert_eq!("SPX   131101C01250000", schwab.to_osi_string());
+
+    let spaced = OptionData::parse_spaced("SPX 131101 C 1,250.00").unwrap();
+    assert_eq!(1250_f64, spaced.strike_price);
+    let ib =
+        OptionData::parse_ib_activity_statement_trades_symbol("SPX 01NOV13 1,250.00 C").unwrap();
+    assert_eq!(1250_f64, ib.strike_price);
+    let ib =
+        OptionData::parse_ib_activity_statement_trades_symbol_locale("SPX 01NOV13 1,250.00 C", '.')
+            .unwrap();
+    assert_eq!(1250_f64, ib.strike_price);
+    assert_eq!(
+        12_345.5,
+        OptionData::parse_schwab("SPX 11/01/2013 12,345.5 C")
+            .unwrap()
+            .strike_price
+    );
+
+    // without a decimal point the comma could be a decimal separator
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_schwab("SPX 11/01/2013 1,250 C")
+    );
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_schwab("SPX 11/01/2013 12,50.00 C")
+    );
+    // with a comma as decimal separator there are no thousands separators
+    assert_eq!(
+        Err(Error::NoResult),
+        OptionData::parse_ib_activity_statement_trades_symbol_locale("SPX 01NOV13 1,250.00 C", ',')
+    );
+    assert_eq!(
+        1.25,
+        OptionData::parse_ib_activity_statement_trades_symbol_locale("SPX 01NOV13 1,25 C", ',')
+            .unwrap()
+            .strike_price
+    );
+}
+
+#[test]
+fn roll_contracts() {
+    let option = OptionData::parse_osi("AAPL  131101C00470000").unwrap();
+
+    let rolled = option.roll_to(2013, 12, 20).unwrap();
+    assert_eq!("AAPL  131220C00470000", rolled.to_osi_string());
+    assert_eq!(option.strike_price, rolled.strike_price);
+    assert_eq!(option.contract_type, rolled.contract_type);
+    assert_eq!("AAPL  131101C00470000", option.to_osi_string());
+
+    assert_eq!(
+        "AAPL  131201C00470000",
+        option.roll_days(30).unwrap().to_osi_string()
+    );
+    assert_eq!(
+        "AAPL  131025C00470000",
+        option.roll_days(-7).unwrap().to_osi_string()
+    );
+
+    assert_eq!(Err(Error::DayOutOfRange), option.roll_to(2013, 11, 31));
+    assert_eq!(Err(Error::MonthOutOfRange), option.roll_to(2013, 13, 1));
+    assert_eq!(Err(Error::YearOutOfRange), option.roll_to(i32::MAX, 11, 1));
+
+    // the year range of the two digit year field is left to the serializers
+    let early = option.roll_to(1999, 11, 1).unwrap();
+    assert_eq!(1999, early.get_expiration_year());
+    assert_eq!(Err(Error::YearOutOfRange), early.try_to_osi_string());
+    assert_eq!(1997, option.roll_days(-6000).unwrap().get_expiration_year());
+    let schwab = OptionData::parse_schwab("AAPL 11/20/1998 470.00 C").unwrap();
+    assert_eq!(
+        "AAPL 11/27/1998 470.00 C",
+        schwab.roll_days(7).unwrap().to_schwab_string()
+    );
+    let far = OptionData::parse_osi_4digit_year("AAPL  21051120C00470000").unwrap();
+    assert_eq!(
+        "AAPL  21051218C00470000",
+        far.roll_days(28).unwrap().to_osi_string_4digit_year()
+    );
+    assert_eq!(Err(Error::YearOutOfRange), option.roll_days(i64::MAX));
+    // rolling is not limited to 2000 to 2099, so the message must not claim that range
+    assert!(!Error::YearOutOfRange.to_string().contains("2099"));
+}
+
+#[test]
+fn strike_formatting() {
+    let strike = Strike::from_f64(32.01).unwrap();
+    assert_eq!(32.01, strike.as_f64());
+    assert_eq!("00032010", strike.as_osi_field());
+    assert_eq!("32.01", strike.as_decimal_string(2));
+    assert_eq!("32.0", strike.as_decimal_string(1));
+    assert_eq!("32", strike.as_decimal_string(0));
+    assert_eq!("32.01000", strike.as_decimal_string(5));
+    assert_eq!("32.01", strike.to_string());
+
+    let strike = Strike::from_f64(0.5).unwrap();
+    assert_eq!("00000500", strike.as_osi_field());
+    assert_eq!("0.50", strike.as_decimal_string(2));
+    assert_eq!("1", strike.as_decimal_string(0));
+    assert_eq!("0.5", strike.to_string());
+
+    assert_eq!("470", Strike::from_f64(470_f64).unwrap().to_string());
+    assert_eq!("12.346", Strike(12_346).to_string());
+    assert_eq!(Err(Error::StrikeOutOfRange), Strike::from_f64(-1_f64));
+    assert_eq!(Err(Error::StrikeOutOfRange), Strike::from_f64(0.0001));
+    assert_eq!(Err(Error::StrikeOutOfRange), Strike::from_f64(100_000_f64));
+}
+
+#[test]
+fn serializers_use_strike() {
+    let ko = OptionData::parse_schwab("KO 05/28/2021 32.01 C").unwrap();
+    assert_eq!(Ok(Strike::from_f64(32.01).unwrap()), ko.strike());
+    assert_eq!("KO    210528C00032010", ko.to_osi_string());
+    assert_eq!("KO210528C00032010", ko.to_osi_string_no_symbol_padding());
+    assert_eq!("KO 05/28/2021 32.01 C", ko.to_schwab_string());
+    assert_eq!("KO 210528 C 32.01", ko.to_spaced_string());
+    assert_eq!("KO 210528 C 00032010", ko.to_opra_string());
+
+    let half = OptionData::parse_osi("SPY   210528P00000500").unwrap();
+    assert_eq!("SPY   210528P00000500", half.to_osi_string());
+    assert_eq!("SPY 05/28/2021 0.50 P", half.to_schwab_string());
+    assert_eq!(".SPY210528P0.5", half.to_tos_string());
+    assert_eq!("SPY 20210528 0.5 P", half.to_ib_tws_string());
+
+    // float noise in the public field does not leak into the output
+    let mut noisy = ko.clone();
+    noisy.strike_price = 32.009_999_999;
+    assert_eq!("KO    210528C00032010", noisy.to_osi_string());
+    assert_eq!("KO 05/28/2021 32.01 C", noisy.to_schwab_string());
+    assert_eq!(
+        "KO 28MAY21 32.01 C",
+        noisy.to_ib_activity_statement_string()
+    );
+
+    // strikes outside of the price field are clamped by the infallible serializers
+    let mut huge = ko.clone();
+    huge.strike_price = 123_456_f64;
+    assert_eq!(Err(Error::StrikeOutOfRange), huge.strike());
+    assert_eq!(Err(Error::StrikeOutOfRange), huge.try_to_osi_string());
+    assert_eq!("KO    210528C99999999", huge.to_osi_string());
+    assert_eq!("KO 210528 C 99999999", huge.to_opra_string());
+    let mut negative = ko.clone();
+    negative.strike_price = -5_f64;
+    assert_eq!(Err(Error::StrikeOutOfRange), negative.try_to_osi_string());
+    assert_eq!("KO    210528C00000000", negative.to_osi_string());
+}
+
+#[test]
+fn ib_activity_statement_century_from_reference() {
+    let nineties = NaiveDate::from_ymd_opt(1997, 6, 30).unwrap();
+    let option = OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+        "KO 20MAR98 40 C",
+        nineties,
+    )
+    .unwrap();
+    assert_eq!(1998, option.get_expiration_year());
+    assert_eq!("KO 20MAR98 40 C", option.to_ib_activity_statement_string());
+    assert_eq!(Err(Error::YearOutOfRange), option.try_to_osi_string());
+    let option = OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+        "KO 28MAY21 32.01 C",
+        nineties,
+    )
+    .unwrap();
+    assert_eq!(2021, option.get_expiration_year());
+
+    // 98 is never read as 2098 unless the reference is at least in 2048
+    let today = NaiveDate::from_ymd_opt(2021, 5, 28).unwrap();
+    let option = OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+        "KO 20MAR98 40 C",
+        today,
+    )
+    .unwrap();
+    assert_eq!(1998, option.get_expiration_year());
+    let option = OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+        "KO 21MAR70 40 C",
+        today,
+    )
+    .unwrap();
+    assert_eq!(2070, option.get_expiration_year());
+    let option = OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+        "KO 20MAR71 40 C",
+        today,
+    )
+    .unwrap();
+    assert_eq!(1971, option.get_expiration_year());
+    let later = NaiveDate::from_ymd_opt(2060, 1, 1).unwrap();
+    let option = OptionData::parse_ib_activity_statement_trades_symbol_with_reference(
+        "KO 20MAR98 40 C",
+        later,
+    )
+    .unwrap();
+    assert_eq!(2098, option.get_expiration_year());
+}
+
+#[test]
+fn try_serialize() {
+    let option = OptionData::parse_schwab("AAPL 11/01/2030 470.00 C").unwrap();
+    assert_eq!(
+        Ok(".AAPL301101C470".to_string()),
+        option.try_serialize(OptionData::to_tos_string)
+    );
+    let option = OptionData::parse_schwab("AAPL 11/01/2105 470.00 C").unwrap();
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        option.try_serialize(OptionData::to_tos_string)
+    );
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        option.try_serialize(OptionData::to_spaced_string)
+    );
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        option.try_serialize(OptionData::to_osi_string_no_symbol_padding)
+    );
+    let mut option = OptionData::parse_schwab("AAPL 11/01/2030 470.00 C").unwrap();
+    option.strike_price = 123_456_f64;
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        option.try_serialize(OptionData::to_opra_string)
+    );
+}
+
+#[test]
+fn ib_activity_statement_century_from_today() {
+    // holds until 2048, from then on `98` is read as 2098
+    let option = OptionData::parse_ib_activity_statement_trades_symbol("KO 20MAR98 40 C").unwrap();
+    assert_eq!(1998, option.get_expiration_year());
+    assert_eq!(Err(Error::YearOutOfRange), option.try_to_osi_string());
+    assert_eq!(
+        2021,
+        OptionData::parse_ib_activity_statement_trades_symbol("KO 28MAY21 32.01 C")
+            .unwrap()
+            .get_expiration_year()
+    );
+}
+
+#[test]
+fn reformat_to_ib_keeps_century() {
+    assert_eq!(
+        Ok("KO 20MAR98 40 C".to_string()),
+        reformat("KO 20MAR98 40 C", Format::Ib, Format::Ib)
+    );
+    assert_eq!(
+        Ok("AAPL 01NOV13 470 C".to_string()),
+        reformat("AAPL 11/01/2013 470.00 C", Format::Schwab, Format::Ib)
+    );
+    // would be read back as 2005 and 2050
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        reformat("AAPL 11/01/2105 470.00 C", Format::Schwab, Format::Ib)
+    );
+    assert_eq!(
+        Err(Error::YearOutOfRange),
+        reformat("AAPL 11/17/1950 470.00 C", Format::Schwab, Format::Ib)
+    );
+}
+
+#[test]
+fn validate_osi_verbose() {
+    assert!(OptionData::validate_osi_verbose("AAPL  131101C00470000").is_empty());
+    assert_eq!(
+        vec![Error::MonthOutOfRange, Error::InvalidContractType],
+        OptionData::validate_osi_verbose("AAPL  131301X00470000")
+    );
+    assert_eq!(
+        vec![
+            Error::LengthOutOfRange { len: 22 },
+            Error::InvalidSymbol,
+            Error::DayOutOfRange,
+            Error::StrikeOutOfRange
+        ],
+        OptionData::validate_osi_verbose("AAPL_  131132C0047000x")
+    );
+}
+
+#[test]
+fn parsers_reject_sub_thousandth_strikes() {
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::parse_ib_activity_statement_trades_symbol("KO 28MAY21 32.0001 C")
+    );
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::parse_schwab("KO 05/28/2021 32.0001 C")
+    );
+    assert_eq!(
+        Err(Error::StrikeOutOfRange),
+        OptionData::parse_tos(".KO210528C32.0001")
+    );
 }